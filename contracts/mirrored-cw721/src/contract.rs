@@ -0,0 +1,207 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult,
+};
+use cw2::{get_contract_version, set_contract_version};
+use cw721::ContractInfoResponse;
+use cw721_base::{Cw721Contract, InstantiateMsg as Cw721InstantiateMsg};
+use semver::Version;
+
+use crate::error::ContractError;
+use crate::msg::{
+    ClassData, ExecuteExt, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryExt, QueryMsg,
+};
+use crate::state::{Extension, MirrorInfo, MIRROR_INFO};
+
+// version info for migration info
+const CONTRACT_NAME: &str = "crates.io:mirrored-cw721";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub type MirroredCw721Contract<'a> = Cw721Contract<'a, Extension, Empty, ExecuteExt, QueryExt>;
+
+/// Handling contract instantiation
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    // the sender is the wrapper instantiating this collection
+    MIRROR_INFO.save(
+        deps.storage,
+        &MirrorInfo {
+            wrapper: info.sender.clone(),
+            original_collection: deps.api.addr_validate(&msg.original_collection)?,
+            original_chain: msg.original_chain,
+            base_uri: msg.base_uri,
+            royalty_info: msg.royalty_info,
+        },
+    )?;
+
+    let res = MirroredCw721Contract::default().instantiate(
+        deps.branch(),
+        env,
+        info,
+        Cw721InstantiateMsg {
+            name: msg.name,
+            symbol: msg.symbol,
+            minter: msg.minter,
+        },
+    )?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(res.add_attributes([
+        ("method", "instantiate"),
+        ("original_collection", &msg.original_collection),
+    ]))
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    // only migrate from the same contract
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrateContract {
+            previous_contract: stored.contract,
+        });
+    }
+
+    // do not allow downgrading the contract
+    let stored_version: Version = stored.version.parse()?;
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    if stored_version > new_version {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "migrate"),
+        ("previous_version", &stored.version),
+        ("new_version", CONTRACT_VERSION),
+    ]))
+}
+
+/// Handling contract execution
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Extension { msg } => {
+            // only the wrapper can execute the extension messages
+            if info.sender != MIRROR_INFO.load(deps.storage)?.wrapper {
+                return Err(ContractError::Unauthorized {});
+            }
+
+            match msg {
+                ExecuteExt::BurnForUnwrap { token_id } => execute_burn_for_unwrap(deps, token_id),
+                ExecuteExt::UpdateTokenMetadata {
+                    token_id,
+                    token_uri,
+                    extension,
+                } => execute_update_token_metadata(deps, token_id, token_uri, extension),
+                ExecuteExt::UpdateCollectionInfo { name, symbol } => {
+                    execute_update_collection_info(deps, name, symbol)
+                }
+            }
+        }
+        _ => Ok(MirroredCw721Contract::default().execute(deps, env, info, msg)?),
+    }
+}
+
+/// Handling contract query
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Extension { msg } => match msg {
+            QueryExt::MirrorInfo {} => to_json_binary(&MIRROR_INFO.load(deps.storage)?),
+            QueryExt::ClassData {} => to_json_binary(&query_class_data(deps)?),
+        },
+        _ => MirroredCw721Contract::default().query(deps, env, msg),
+    }
+}
+
+pub fn query_class_data(deps: Deps) -> StdResult<ClassData> {
+    let mirror_info = MIRROR_INFO.load(deps.storage)?;
+    let contract_info = MirroredCw721Contract::default()
+        .contract_info
+        .load(deps.storage)?;
+
+    Ok(ClassData {
+        name: contract_info.name,
+        symbol: contract_info.symbol,
+        original_collection: mirror_info.original_collection.to_string(),
+        original_chain: mirror_info.original_chain,
+        base_uri: mirror_info.base_uri,
+        royalty_info: mirror_info.royalty_info,
+        wrapper: mirror_info.wrapper.to_string(),
+    })
+}
+
+pub fn execute_burn_for_unwrap(deps: DepsMut, token_id: String) -> Result<Response, ContractError> {
+    let contract = MirroredCw721Contract::default();
+
+    // the wrapper can burn the token without being approved
+    contract.tokens.load(deps.storage, &token_id)?;
+    contract.tokens.remove(deps.storage, &token_id)?;
+    contract.decrement_tokens(deps.storage)?;
+
+    Ok(Response::new().add_attributes([("method", "burn_for_unwrap"), ("token_id", &token_id)]))
+}
+
+pub fn execute_update_token_metadata(
+    deps: DepsMut,
+    token_id: String,
+    token_uri: Option<String>,
+    extension: Extension,
+) -> Result<Response, ContractError> {
+    MirroredCw721Contract::default().tokens.update(
+        deps.storage,
+        &token_id,
+        |token| match token {
+            Some(mut token) => {
+                token.token_uri = token_uri;
+                token.extension = extension;
+                Ok(token)
+            }
+            None => Err(StdError::not_found("token")),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attributes([("method", "update_token_metadata"), ("token_id", &token_id)]))
+}
+
+pub fn execute_update_collection_info(
+    deps: DepsMut,
+    name: Option<String>,
+    symbol: Option<String>,
+) -> Result<Response, ContractError> {
+    let contract_info = MirroredCw721Contract::default().contract_info.update(
+        deps.storage,
+        |contract_info| -> StdResult<ContractInfoResponse> {
+            Ok(ContractInfoResponse {
+                name: name.unwrap_or(contract_info.name),
+                symbol: symbol.unwrap_or(contract_info.symbol),
+            })
+        },
+    )?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "update_collection_info"),
+        ("name", &contract_info.name),
+        ("symbol", &contract_info.symbol),
+    ]))
+}
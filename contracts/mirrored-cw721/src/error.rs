@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("{0}")]
+    Base(#[from] cw721_base::ContractError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Cannot migrate from contract {previous_contract:?}")]
+    CannotMigrateContract { previous_contract: String },
+
+    #[error("Cannot migrate from version {previous_version:?} to older version {new_version:?}")]
+    CannotMigrateVersion {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
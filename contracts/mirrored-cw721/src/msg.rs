@@ -0,0 +1,69 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::CustomMsg;
+
+use crate::state::{Extension, MirrorInfo, RoyaltyInfo};
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub minter: String,
+    pub original_collection: String,
+    pub original_chain: Option<String>,
+    pub base_uri: Option<String>,
+    pub royalty_info: Option<RoyaltyInfo>,
+}
+
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// the extension messages only the wrapper can execute
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteExt {
+    /// burn a mirrored token whose original token is released by the wrapper
+    BurnForUnwrap { token_id: String },
+    UpdateTokenMetadata {
+        token_id: String,
+        token_uri: Option<String>,
+        extension: Extension,
+    },
+    UpdateCollectionInfo {
+        name: Option<String>,
+        symbol: Option<String>,
+    },
+}
+impl CustomMsg for ExecuteExt {}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryExt {
+    /// the wrapper and the original collection behind this mirrored collection
+    #[returns(MirrorInfo)]
+    MirrorInfo {},
+    /// the class data carried by ics721 when the collection is bridged
+    #[returns(ClassData)]
+    ClassData {},
+}
+impl CustomMsg for QueryExt {}
+
+/// the ics721 class data of a mirrored collection
+#[cw_serde]
+pub struct ClassData {
+    pub name: String,
+    pub symbol: String,
+    pub original_collection: String,
+    /// the chain the original collection was created on, none if unknown
+    pub original_chain: Option<String>,
+    pub base_uri: Option<String>,
+    pub royalty_info: Option<RoyaltyInfo>,
+    pub wrapper: String,
+}
+
+/// Message type for `execute` entry_point
+pub type ExecuteMsg = cw721_base::ExecuteMsg<Extension, ExecuteExt>;
+
+/// Message type for `query` entry_point
+pub type QueryMsg = cw721_base::QueryMsg<QueryExt>;
@@ -0,0 +1,55 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Addr;
+use cw_storage_plus::Item;
+
+// the wrapper and the original collection behind this mirrored collection
+pub const MIRROR_INFO: Item<MirrorInfo> = Item::new("mirror-info");
+
+#[cw_serde]
+pub struct MirrorInfo {
+    // the wrapper instantiating this collection, the only one allowed to use the extension messages
+    pub wrapper: Addr,
+    pub original_collection: Addr,
+    // the chain the original collection was created on, none if unknown
+    pub original_chain: Option<String>,
+    pub base_uri: Option<String>,
+    pub royalty_info: Option<RoyaltyInfo>,
+}
+
+/// the royalty of all tokens of the collection
+#[cw_serde]
+pub struct RoyaltyInfo {
+    pub payment_address: String,
+    // the percentage of a sale owed to the payment address
+    pub royalty_percentage: u64,
+}
+
+// this extension is used to store metadata of cw2981 token
+pub type Extension = Option<Metadata>;
+#[cw_serde]
+#[derive(Default)]
+pub struct Metadata {
+    pub image: Option<String>,
+    pub image_data: Option<String>,
+    pub external_url: Option<String>,
+    pub description: Option<String>,
+    pub name: Option<String>,
+    pub attributes: Option<Vec<Trait>>,
+    pub background_color: Option<String>,
+    pub animation_url: Option<String>,
+    pub youtube_url: Option<String>,
+    /// This is how much the minter takes as a cut when sold
+    /// royalties are owed on this token if it is Some
+    pub royalty_percentage: Option<u64>,
+    /// The payment address, may be different to or the same
+    /// as the minter addr
+    /// question: how do we validate this?
+    pub royalty_payment_address: Option<String>,
+}
+
+#[cw_serde]
+pub struct Trait {
+    pub display_type: Option<String>,
+    pub trait_type: String,
+    pub value: String,
+}
@@ -0,0 +1,287 @@
+#![cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate, migrate, query, MirroredCw721Contract};
+    use crate::error::ContractError;
+    use crate::msg::{
+        ClassData, ExecuteExt, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryExt, QueryMsg,
+    };
+    use crate::state::{Metadata, MirrorInfo, RoyaltyInfo};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+    };
+    use cosmwasm_std::{from_json, Addr, OwnedDeps, Response};
+    use cw2::{get_contract_version, set_contract_version};
+    use cw721::ContractInfoResponse;
+
+    const WRAPPER: &str = "wrapper";
+    const ORIGINAL_COLLECTION: &str = "original";
+    const USER: &str = "user";
+    const ORIGINAL_CHAIN: &str = "aura-testnet";
+
+    fn royalty_info() -> RoyaltyInfo {
+        RoyaltyInfo {
+            payment_address: "artist".to_string(),
+            royalty_percentage: 5,
+        }
+    }
+
+    // instantiate the collection by the wrapper and mint a token to the user
+    fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(WRAPPER, &[]),
+            InstantiateMsg {
+                name: "Mirrored Collection".to_string(),
+                symbol: "MIRRORED".to_string(),
+                minter: WRAPPER.to_string(),
+                original_collection: ORIGINAL_COLLECTION.to_string(),
+                original_chain: Some(ORIGINAL_CHAIN.to_string()),
+                base_uri: None,
+                royalty_info: Some(royalty_info()),
+            },
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(WRAPPER, &[]),
+            ExecuteMsg::Mint {
+                token_id: "1".to_string(),
+                owner: USER.to_string(),
+                token_uri: Some("ipfs://original/1".to_string()),
+                extension: None,
+            },
+        )
+        .unwrap();
+        deps
+    }
+
+    fn execute_ext(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        sender: &str,
+        msg: ExecuteExt,
+    ) -> Result<Response, ContractError> {
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info(sender, &[]),
+            ExecuteMsg::Extension { msg },
+        )
+    }
+
+    #[test]
+    fn instantiate_saves_the_mirror_info() {
+        let deps = setup();
+
+        let mirror_info: MirrorInfo = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Extension {
+                    msg: QueryExt::MirrorInfo {},
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            mirror_info,
+            MirrorInfo {
+                wrapper: Addr::unchecked(WRAPPER),
+                original_collection: Addr::unchecked(ORIGINAL_COLLECTION),
+                original_chain: Some(ORIGINAL_CHAIN.to_string()),
+                base_uri: None,
+                royalty_info: Some(royalty_info()),
+            }
+        );
+    }
+
+    #[test]
+    fn only_the_wrapper_executes_the_extension() {
+        let mut deps = setup();
+
+        // even the owner of the token cannot use the extension messages
+        let err = execute_ext(
+            &mut deps,
+            USER,
+            ExecuteExt::BurnForUnwrap {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), ContractError::Unauthorized {}.to_string());
+
+        let err = execute_ext(
+            &mut deps,
+            USER,
+            ExecuteExt::UpdateCollectionInfo {
+                name: Some("Renamed".to_string()),
+                symbol: None,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), ContractError::Unauthorized {}.to_string());
+    }
+
+    #[test]
+    fn wrapper_burns_without_approval() {
+        let mut deps = setup();
+
+        execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::BurnForUnwrap {
+                token_id: "1".to_string(),
+            },
+        )
+        .unwrap();
+
+        let contract = MirroredCw721Contract::default();
+        assert!(!contract.tokens.has(deps.as_ref().storage, "1"));
+        assert_eq!(contract.token_count(deps.as_ref().storage).unwrap(), 0);
+
+        // the token cannot be burned twice
+        assert!(execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::BurnForUnwrap {
+                token_id: "1".to_string(),
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn wrapper_updates_token_metadata() {
+        let mut deps = setup();
+
+        let extension = Some(Metadata {
+            name: Some("Token 1".to_string()),
+            ..Metadata::default()
+        });
+        execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::UpdateTokenMetadata {
+                token_id: "1".to_string(),
+                token_uri: Some("ipfs://revealed/1".to_string()),
+                extension: extension.clone(),
+            },
+        )
+        .unwrap();
+
+        let token = MirroredCw721Contract::default()
+            .tokens
+            .load(deps.as_ref().storage, "1")
+            .unwrap();
+        assert_eq!(token.token_uri, Some("ipfs://revealed/1".to_string()));
+        assert_eq!(token.extension, extension);
+        assert_eq!(token.owner, Addr::unchecked(USER));
+
+        // a missing token cannot be updated
+        assert!(execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::UpdateTokenMetadata {
+                token_id: "2".to_string(),
+                token_uri: None,
+                extension: None,
+            },
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn wrapper_updates_collection_info() {
+        let mut deps = setup();
+
+        // the symbol is kept when not given
+        execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::UpdateCollectionInfo {
+                name: Some("Renamed".to_string()),
+                symbol: None,
+            },
+        )
+        .unwrap();
+
+        let contract_info: ContractInfoResponse =
+            from_json(query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap())
+                .unwrap();
+        assert_eq!(contract_info.name, "Renamed");
+        assert_eq!(contract_info.symbol, "MIRRORED");
+    }
+
+    #[test]
+    fn class_data_follows_the_collection_info() {
+        let mut deps = setup();
+
+        execute_ext(
+            &mut deps,
+            WRAPPER,
+            ExecuteExt::UpdateCollectionInfo {
+                name: Some("Renamed".to_string()),
+                symbol: None,
+            },
+        )
+        .unwrap();
+
+        let class_data: ClassData = from_json(
+            query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Extension {
+                    msg: QueryExt::ClassData {},
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(
+            class_data,
+            ClassData {
+                name: "Renamed".to_string(),
+                symbol: "MIRRORED".to_string(),
+                original_collection: ORIGINAL_COLLECTION.to_string(),
+                original_chain: Some(ORIGINAL_CHAIN.to_string()),
+                base_uri: None,
+                royalty_info: Some(royalty_info()),
+                wrapper: WRAPPER.to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn migration_checks_the_contract_and_version() {
+        let mut deps = setup();
+        let stored = get_contract_version(deps.as_ref().storage).unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+        // the contract cannot be downgraded
+        set_contract_version(deps.as_mut().storage, &stored.contract, "999.0.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::CannotMigrateVersion {
+                previous_version: "999.0.0".to_string(),
+                new_version: stored.version.clone(),
+            }
+            .to_string()
+        );
+
+        // only the same contract is migrated
+        set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.18.0").unwrap();
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            ContractError::CannotMigrateContract {
+                previous_contract: "crates.io:cw721-base".to_string(),
+            }
+            .to_string()
+        );
+    }
+}
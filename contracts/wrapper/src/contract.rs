@@ -1,28 +1,65 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env, MessageInfo, QueryRequest,
-    Reply, ReplyOn, Response, StdResult, SubMsg, WasmMsg, WasmQuery,
+    from_json, to_json_binary, to_json_string, Addr, Binary, CosmosMsg, Deps, DepsMut, Empty, Env,
+    IbcTimeout, MessageInfo, Order, QueryRequest, Reply, ReplyOn, Response, StdError, StdResult,
+    Storage, SubMsg, Timestamp, WasmMsg, WasmQuery,
 };
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 use cw721::{
-    Cw721ExecuteMsg, Cw721QueryMsg, NftInfoResponse as Cw721NftInfoResponse, OwnerOfResponse,
+    Cw721ExecuteMsg, Cw721QueryMsg, Cw721ReceiveMsg, NftInfoResponse as Cw721NftInfoResponse,
+    OperatorResponse, OwnerOfResponse,
 };
-use cw721_base::msg::{ExecuteMsg as Cw721BaseExecuteMsg, InstantiateMsg as Cw721InstantiateMsg};
+use cw721_base::msg::ExecuteMsg as Cw721BaseExecuteMsg;
+use cw_storage_plus::Bound;
 use cw_utils::parse_reply_instantiate_data;
+use mirrored_cw721::msg::{
+    ClassData, ExecuteExt as MirrorExecuteExt, InstantiateMsg as MirroredCw721InstantiateMsg,
+    QueryExt as MirrorQueryExt, QueryMsg as MirroredCw721QueryMsg,
+};
+use mirrored_cw721::state::MirrorInfo;
+use semver::Version;
+use serde::de::IgnoredAny;
 
 use crate::error::ContractError;
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::ics721::{
+    parse_class_trace, validate_collection_info, validate_receiver, validate_token_id,
+    IbcOutgoingMsg, Ics721AckCallbackMsg, Ics721Callbacks, Ics721Memo, Ics721QueryMsg,
+    Ics721Status,
+};
+use crate::migrations;
+use crate::msg::{
+    ExecuteMsg, InstantiateMsg, InstantiateOptions, MigrateMsg, PostAction, PostActionMsg,
+    QueryMsg, ReceiveMsg, SudoMsg, TokenIdCheckResponse, TokenLinkResponse,
+};
 use crate::state::{
-    ContractInfoResponse as Cw721ContractInfoResponse, Extension, MirroredData, WrapData,
-    CONTROLLER, CW721_CODE_ID, MIRRORED_COLLECTIONS, ORIGINAL_COLLECTIONS, TOTAL_WRAPPED,
-    WRAP_DATA,
+    BridgeState, BridgeStatus, ChannelInfo, Config,
+    ContractInfoResponse as Cw721ContractInfoResponse, Escrow, EscrowStatus, Extension,
+    Ics721Policy, MirroredData, Provenance, RateLimit, Recovery, TokenIdStrategy, WrapData,
+    BRIDGE_CHANNELS, BRIDGE_STATES, CONFIG, ESCROWS, LAST_REFRESHED, MIRRORED_COLLECTIONS,
+    MIRRORED_TOKEN_IDS, ORIGINAL_COLLECTIONS, ORIGINAL_TOKEN_IDS, RATE_LIMITS, RECOVERIES,
+    RELAYERS, WRAP_DATA,
 };
+use crate::token_uri::{is_revealed, mirrored_token_uri, sha256_hex, validate_token_uri_template};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:wrapper-seekhype";
 const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
+// pagination info for iterating over wrap data
+const DEFAULT_LIMIT: u32 = 10;
+const MAX_LIMIT: u32 = 30;
+
+// the default minimum seconds between two metadata refreshes of a mirrored token
+pub const DEFAULT_REFRESH_INTERVAL: u64 = 3600;
+
+// the default seconds before a bridge transfer times out
+pub const DEFAULT_BRIDGE_TIMEOUT: u64 = 600;
+
+// the default seconds between the proposal and the execution of a recovery,
+// also the shortest timelock the controller can set
+pub const DEFAULT_RECOVERY_TIMELOCK: u64 = 7 * 24 * 3600;
+
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -33,20 +70,75 @@ pub fn instantiate(
 ) -> Result<Response, ContractError> {
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
-    // update controller
-    CONTROLLER.save(deps.storage, &deps.api.addr_validate(&msg.controller)?)?;
-
-    // update cw721 code id
-    CW721_CODE_ID.save(deps.storage, &msg.cw721_code_id)?;
+    let recovery_timelock = msg.recovery_timelock.unwrap_or(DEFAULT_RECOVERY_TIMELOCK);
+    validate_recovery_timelock(recovery_timelock)?;
 
-    // init total wrapped
-    TOTAL_WRAPPED.save(deps.storage, &0u64)?;
+    // update controller, cw721 code id and init total wrapped
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            controller: deps.api.addr_validate(&msg.controller)?,
+            cw721_code_id: msg.cw721_code_id,
+            total_wrapped: 0u64,
+            paused: false,
+            wrapper_as_mirror_admin: msg.wrapper_as_mirror_admin.unwrap_or(false),
+            refresh_interval: msg.refresh_interval.unwrap_or(DEFAULT_REFRESH_INTERVAL),
+            ics721: msg
+                .ics721
+                .as_ref()
+                .map(|ics721| deps.api.addr_validate(ics721))
+                .transpose()?,
+            bridge_timeout: msg.bridge_timeout.unwrap_or(DEFAULT_BRIDGE_TIMEOUT),
+            ics721_policy: msg.ics721_policy,
+            recovery_timelock,
+        },
+    )?;
 
     // now we instantiate the cw20 contract
     Ok(Response::new().add_attributes([
         ("method", "instantiate"),
         ("controller", &msg.controller),
         ("cw721_code_id", &msg.cw721_code_id.to_string()),
+        (
+            "wrapper_as_mirror_admin",
+            &msg.wrapper_as_mirror_admin.unwrap_or(false).to_string(),
+        ),
+    ]))
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+
+    // only migrate from the same contract
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::CannotMigrateContract {
+            previous_contract: stored.contract,
+        });
+    }
+
+    // do not allow downgrading the contract
+    let stored_version: Version = stored.version.parse()?;
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    if stored_version > new_version {
+        return Err(ContractError::CannotMigrateVersion {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    // run the state migrations in order
+    if stored_version < Version::new(0, 2, 0) {
+        migrations::v0_2_0(deps.branch())?;
+    }
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "migrate"),
+        ("previous_version", &stored.version),
+        ("new_version", CONTRACT_VERSION),
     ]))
 }
 
@@ -62,21 +154,179 @@ pub fn execute(
         ExecuteMsg::Wrap {
             collection_address,
             token_ids,
-        } => execute_wrap(deps, env, info, collection_address, token_ids),
+            mirror_index,
+            recipient,
+            owner,
+            then,
+        } => execute_wrap(
+            deps,
+            env,
+            info,
+            collection_address,
+            token_ids,
+            mirror_index,
+            recipient,
+            owner,
+            then,
+        ),
         ExecuteMsg::Unwrap {
             collection_address,
             token_ids,
-        } => execute_unwrap(deps, env, info, collection_address, token_ids),
+            recipient,
+            owner,
+            then,
+        } => execute_unwrap(
+            deps,
+            env,
+            info,
+            collection_address,
+            token_ids,
+            recipient,
+            owner,
+            then,
+        ),
+        ExecuteMsg::UnwrapAndBridge {
+            collection_address,
+            token_ids,
+            channel_id,
+            receiver,
+            timeout_seconds,
+        } => execute_unwrap_and_bridge(
+            deps,
+            env,
+            info,
+            collection_address,
+            token_ids,
+            channel_id,
+            receiver,
+            timeout_seconds,
+        ),
+        ExecuteMsg::ReceiveNft(receive_msg) => execute_receive_nft(deps, env, info, receive_msg),
+        ExecuteMsg::Ics721AckCallback(ack) => execute_ics721_ack_callback(deps, env, info, ack),
+        ExecuteMsg::SyncBridgeStates {
+            collection_address,
+            token_ids,
+        } => execute_sync_bridge_states(deps, env, collection_address, token_ids),
+        ExecuteMsg::SetBridgeChannel {
+            channel_id,
+            chain_id,
+            bech32_prefix,
+            convert_local_receiver,
+        } => execute_set_bridge_channel(
+            deps,
+            info,
+            channel_id,
+            chain_id,
+            bech32_prefix,
+            convert_local_receiver,
+        ),
+        ExecuteMsg::RemoveBridgeChannel { channel_id } => {
+            execute_remove_bridge_channel(deps, info, channel_id)
+        }
+        ExecuteMsg::SetRateLimit {
+            channel_id,
+            max_tokens,
+            window_seconds,
+        } => execute_set_rate_limit(deps, env, info, channel_id, max_tokens, window_seconds),
+        ExecuteMsg::RemoveRateLimit { channel_id } => {
+            execute_remove_rate_limit(deps, info, channel_id)
+        }
+        ExecuteMsg::ProposeRecovery {
+            collection_address,
+            token_id,
+            recipient,
+        } => execute_propose_recovery(deps, env, info, collection_address, token_id, recipient),
+        ExecuteMsg::ExecuteRecovery {
+            collection_address,
+            token_id,
+        } => execute_execute_recovery(deps, env, collection_address, token_id),
+        ExecuteMsg::CancelRecovery {
+            collection_address,
+            token_id,
+        } => execute_cancel_recovery(deps, info, collection_address, token_id),
+        ExecuteMsg::SetIcs721Policy { policy } => execute_set_ics721_policy(deps, info, policy),
         ExecuteMsg::RegisterCollection {
             original_collection,
             new_collection,
-        } => execute_register_collection(deps, env, info, original_collection, new_collection),
+            instantiate_options,
+        } => execute_register_collection(
+            deps,
+            env,
+            info,
+            original_collection,
+            new_collection,
+            instantiate_options,
+        ),
+        ExecuteMsg::UpdateController { controller } => {
+            execute_update_controller(deps, info, controller)
+        }
+        ExecuteMsg::SetPaused { paused } => execute_set_paused(deps, info, paused),
+        ExecuteMsg::AddRelayer { relayer } => execute_add_relayer(deps, info, relayer),
+        ExecuteMsg::RemoveRelayer { relayer } => execute_remove_relayer(deps, info, relayer),
+        ExecuteMsg::SetCollectionStatus {
+            collection_address,
+            active,
+        } => execute_set_collection_status(deps, info, collection_address, active),
+        ExecuteMsg::UpdateCollectionInfo {
+            collection_address,
+            name,
+            symbol,
+        } => execute_update_collection_info(deps, info, collection_address, name, symbol),
+        ExecuteMsg::UpdateConfig {
+            cw721_code_id,
+            wrapper_as_mirror_admin,
+            refresh_interval,
+            ics721,
+            bridge_timeout,
+            recovery_timelock,
+        } => execute_update_config(
+            deps,
+            info,
+            cw721_code_id,
+            wrapper_as_mirror_admin,
+            refresh_interval,
+            ics721,
+            bridge_timeout,
+            recovery_timelock,
+        ),
+        ExecuteMsg::MigrateMirrors {
+            code_id,
+            msg,
+            start_after,
+            limit,
+        } => execute_migrate_mirrors(deps, env, info, code_id, msg, start_after, limit),
+        ExecuteMsg::Reveal {
+            collection_address,
+            token_ids,
+        } => execute_reveal(deps, env, info, collection_address, token_ids),
+        ExecuteMsg::RefreshMetadata {
+            collection_address,
+            token_ids,
+        } => execute_refresh_metadata(deps, env, collection_address, token_ids),
+    }
+}
+
+/// Handling chain governance overrides
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateController { controller } => update_controller(deps, controller),
+        SudoMsg::SetPaused { paused } => set_paused(deps, paused),
+        SudoMsg::SetCollectionStatus {
+            collection_address,
+            active,
+        } => set_collection_status(deps, collection_address, active),
+        SudoMsg::ForceUnwrap {
+            collection_address,
+            token_ids,
+            recipient,
+        } => force_unwrap(deps, env, collection_address, token_ids, recipient),
     }
 }
 
 /// Handling contract query
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Controller {} => to_json_binary(&query_controller(deps)?),
         QueryMsg::OriginalCollectionInfo { collection_address } => to_json_binary(
@@ -88,6 +338,73 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
                 .load(deps.storage, deps.api.addr_validate(&collection_address)?)?,
         ),
         QueryMsg::WrapData { index } => to_json_binary(&WRAP_DATA.load(deps.storage, index)?),
+        QueryMsg::MirroredTokenId {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_mirrored_token_id(
+            deps,
+            collection_address,
+            token_id,
+        )?),
+        QueryMsg::OriginalTokenId {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_original_token_id(
+            deps,
+            collection_address,
+            token_id,
+        )?),
+        QueryMsg::TokenLink {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_token_link(deps, collection_address, token_id)?),
+        QueryMsg::Relayers { start_after, limit } => {
+            to_json_binary(&query_relayers(deps, start_after, limit)?)
+        }
+        QueryMsg::CheckTokenIds {
+            collection_address,
+            mirror_index,
+            token_ids,
+        } => to_json_binary(&query_check_token_ids(
+            deps,
+            collection_address,
+            mirror_index,
+            token_ids,
+        )?),
+        QueryMsg::ClassData { collection_address } => {
+            to_json_binary(&query_class_data(deps, env, collection_address)?)
+        }
+        QueryMsg::Provenance { collection_address } => {
+            to_json_binary(&query_provenance(deps, collection_address)?)
+        }
+        QueryMsg::RateLimit { channel_id } => to_json_binary(
+            &RATE_LIMITS
+                .may_load(deps.storage, &channel_id)?
+                .map(|rate_limit| rate_limit.at(env.block.time)),
+        ),
+        QueryMsg::Recovery {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_recovery(deps, collection_address, token_id)?),
+        QueryMsg::BridgeChannel { channel_id } => {
+            to_json_binary(&BRIDGE_CHANNELS.load(deps.storage, &channel_id)?)
+        }
+        QueryMsg::BridgeState {
+            collection_address,
+            token_id,
+        } => to_json_binary(&query_bridge_state(deps, collection_address, token_id)?),
+        QueryMsg::TokensInBridgeStatus {
+            collection_address,
+            status,
+            start_after,
+            limit,
+        } => to_json_binary(&query_tokens_in_bridge_status(
+            deps,
+            collection_address,
+            status,
+            start_after,
+            limit,
+        )?),
     }
 }
 
@@ -128,83 +445,156 @@ pub fn execute_register_collection(
     info: MessageInfo,
     original_collection: String,
     new_collection: MirroredData,
+    instantiate_options: Option<InstantiateOptions>,
 ) -> Result<Response, ContractError> {
     // only controller can register collection
-    if info.sender != CONTROLLER.load(deps.storage)? {
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
         return Err(ContractError::Unauthorized {});
     }
 
+    // the token uri template must be valid before any token is minted
+    if let Some(template) = &new_collection.token_uri_template {
+        validate_token_uri_template(template)?;
+    }
+
+    // the royalty cannot exceed the sale price
+    if let Some(royalty_info) = &new_collection.royalty_info {
+        if royalty_info.royalty_percentage > 100 {
+            return Err(ContractError::InvalidRoyaltyPercentage {
+                val: royalty_info.royalty_percentage,
+            });
+        }
+    }
+
     let mut res = Response::new();
-    // if the original collection is not in the list, then we must create new mirror for it
-    if !ORIGINAL_COLLECTIONS.has(deps.storage, deps.api.addr_validate(&original_collection)?) {
-        // query contract info of original collection
-        let contract_info_response: StdResult<Cw721ContractInfoResponse> =
-            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: original_collection.clone(),
-                msg: to_json_binary(&Cw721QueryMsg::ContractInfo {})?,
-            }));
+    // every registration creates a new mirror for the original collection
+    // query contract info of original collection
+    let contract_info_response: StdResult<Cw721ContractInfoResponse> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: original_collection.clone(),
+            msg: to_json_binary(&Cw721QueryMsg::ContractInfo {})?,
+        }));
+
+    match contract_info_response {
+        Ok(contract_info) => {
+            let mirrored_name = new_collection
+                .collection_name
+                .clone()
+                .unwrap_or(contract_info.name);
+            let mirrored_symbol = new_collection
+                .collection_symbol
+                .clone()
+                .unwrap_or(contract_info.symbol);
+
+            // the mirrored collection must be bridgeable by ics721
+            if let Some(policy) = &config.ics721_policy {
+                validate_collection_info(policy, &mirrored_name, &mirrored_symbol)?;
+            }
 
-        match contract_info_response {
-            Ok(contract_info) => {
-                let mirrored_name = new_collection
-                    .collection_name
-                    .clone()
-                    .unwrap_or(contract_info.name);
-                let mirrored_symbol = new_collection
-                    .collection_symbol
-                    .clone()
-                    .unwrap_or(contract_info.symbol);
-
-                // cw721 instantiate msg
-                let cw721_instantiation_msg = Cw721InstantiateMsg {
+            let instantiate_options = instantiate_options.unwrap_or(InstantiateOptions {
+                admin: None,
+                label: None,
+                code_id: None,
+                msg_template: None,
+            });
+
+            let provenance = query_provenance_of_collection(deps.as_ref(), &original_collection)?;
+            let original_chain = original_chain(provenance.as_ref(), &env);
+
+            // cw721 instantiate msg, either from the custom template or the mirrored-cw721 one
+            let cw721_instantiation_msg = match instantiate_options.msg_template {
+                Some(msg_template) => {
+                    let msg = render_instantiate_template(
+                        &msg_template,
+                        &[
+                            ("{name}", to_json_string(&mirrored_name)?),
+                            ("{symbol}", to_json_string(&mirrored_symbol)?),
+                            ("{minter}", to_json_string(&env.contract.address)?),
+                        ],
+                    )?;
+                    // the rendered template must be a valid json
+                    if from_json::<IgnoredAny>(&msg).is_err() {
+                        return Err(ContractError::InvalidInstantiateTemplate { val: msg });
+                    }
+                    Binary::from(msg.into_bytes())
+                }
+                None => to_json_binary(&MirroredCw721InstantiateMsg {
                     name: mirrored_name,
                     symbol: mirrored_symbol,
                     minter: env.contract.address.to_string(),
-                };
+                    original_collection: original_collection.clone(),
+                    original_chain,
+                    base_uri: new_collection.base_uri.clone(),
+                    royalty_info: new_collection.royalty_info.clone(),
+                })?,
+            };
 
-                // increase total wrapped
-                let mut total_wrapped = TOTAL_WRAPPED.load(deps.storage)?;
-                total_wrapped += 1;
-                TOTAL_WRAPPED.save(deps.storage, &total_wrapped)?;
-
-                // instantiate new mirror collection
-                res = res.add_submessage(SubMsg {
-                    id: total_wrapped,
-                    gas_limit: None,
-                    msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
-                        admin: Some(CONTROLLER.load(deps.storage).unwrap().to_string()),
-                        code_id: CW721_CODE_ID.load(deps.storage)?,
-                        msg: to_json_binary(&cw721_instantiation_msg)?,
-                        funds: vec![],
-                        label: format!("Intantiate mirror collection for {}", original_collection),
-                    }),
-                    reply_on: ReplyOn::Success,
-                });
+            // increase total wrapped
+            config.total_wrapped += 1;
+            CONFIG.save(deps.storage, &config)?;
+            let total_wrapped = config.total_wrapped;
 
-                // now update all data
-                // update original collection mapping
-                ORIGINAL_COLLECTIONS.save(
-                    deps.storage,
-                    deps.api.addr_validate(&original_collection)?,
-                    &total_wrapped,
-                )?;
-                // the mirrored collection mapping will be updated in reply
-                // update wrap data with the status of active = false
-                let wrap_data = WrapData {
-                    original_collection: deps.api.addr_validate(&original_collection)?,
-                    mirrored_collection: Addr::unchecked(""),
-                    mirrored_data: MirroredData {
-                        collection_name: new_collection.collection_name,
-                        collection_symbol: new_collection.collection_symbol,
-                        base_uri: new_collection.base_uri,
-                    },
-                    active: false,
-                };
-                WRAP_DATA.save(deps.storage, total_wrapped, &wrap_data)?;
-            }
-            Err(_) => {
-                return Err(ContractError::Unauthorized {});
-            }
+            // the wasm admin and code id of the mirrored collection
+            let admin = match instantiate_options.admin {
+                Some(admin) => deps.api.addr_validate(&admin)?,
+                None if config.wrapper_as_mirror_admin => env.contract.address.clone(),
+                None => config.controller.clone(),
+            };
+            let code_id = instantiate_options.code_id.unwrap_or(config.cw721_code_id);
+
+            // instantiate new mirror collection
+            res = res.add_submessage(SubMsg {
+                id: total_wrapped,
+                gas_limit: None,
+                msg: CosmosMsg::Wasm(WasmMsg::Instantiate {
+                    admin: Some(admin.to_string()),
+                    code_id,
+                    msg: cw721_instantiation_msg,
+                    funds: info.funds,
+                    label: instantiate_options.label.unwrap_or(format!(
+                        "Instantiate mirror collection for {}",
+                        original_collection
+                    )),
+                }),
+                reply_on: ReplyOn::Success,
+            });
+
+            // now update all data
+            // append the new mirror to the original collection mapping
+            let original_collection_addr = deps.api.addr_validate(&original_collection)?;
+            let mut mirrors = ORIGINAL_COLLECTIONS
+                .may_load(deps.storage, original_collection_addr.clone())?
+                .unwrap_or_default();
+            mirrors.push(total_wrapped);
+            ORIGINAL_COLLECTIONS.save(deps.storage, original_collection_addr, &mirrors)?;
+            // the mirrored collection mapping will be updated in reply
+            // update wrap data with the status of active = false
+            let wrap_data = WrapData {
+                original_collection: deps.api.addr_validate(&original_collection)?,
+                mirrored_collection: Addr::unchecked(""),
+                mirrored_data: MirroredData {
+                    collection_name: new_collection.collection_name,
+                    collection_symbol: new_collection.collection_symbol,
+                    base_uri: new_collection.base_uri,
+                    token_id_strategy: new_collection.token_id_strategy,
+                    token_uri_template: new_collection.token_uri_template,
+                    reveal: new_collection.reveal,
+                    royalty_info: new_collection.royalty_info,
+                },
+                active: false,
+                code_id,
+                admin: Some(admin),
+                escrowed: 0,
+                token_id_sequence: 0,
+                revealed: false,
+                bridged_out: 0,
+                provenance,
+            };
+            WRAP_DATA.save(deps.storage, total_wrapped, &wrap_data)?;
+        }
+        Err(_) => {
+            return Err(ContractError::Unauthorized {});
         }
     }
 
@@ -214,193 +604,2229 @@ pub fn execute_register_collection(
     ]))
 }
 
-pub fn execute_wrap(
+pub fn execute_update_controller(
     deps: DepsMut,
-    env: Env,
     info: MessageInfo,
-    collection_address: String,
-    token_ids: Vec<String>,
+    controller: String,
 ) -> Result<Response, ContractError> {
-    // if the collection is not registered, then return error
-    if !ORIGINAL_COLLECTIONS.has(deps.storage, deps.api.addr_validate(&collection_address)?) {
-        return Err(ContractError::CollectionNotAllowed {});
+    // only controller can update controller
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    // if the original collection status is not active, then return error
-    let wrap_data = WRAP_DATA.load(
-        deps.storage,
-        ORIGINAL_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?,
-    )?;
-    if !wrap_data.active {
-        return Err(ContractError::CollectionDeactivated {});
-    }
+    update_controller(deps, controller)
+}
 
-    let mut res = Response::new();
+pub fn execute_set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    // only controller can pause the contract
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
 
-    for token_id in token_ids.iter() {
-        // if the token id are not owned by the sender, then return error
-        let owner_response: StdResult<OwnerOfResponse> =
-            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: collection_address.clone(),
-                msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
-                    token_id: token_id.to_string(),
-                    include_expired: None,
-                })?,
-            }));
-        match owner_response {
-            Ok(owner) => {
-                if owner.owner != info.sender {
-                    return Err(ContractError::NotOwnedBySender {
-                        val: token_id.to_string(),
-                    });
-                }
-            }
-            Err(_) => {
-                return Err(ContractError::NotOwnedBySender {
-                    val: token_id.to_string(),
-                });
-            }
-        }
+    set_paused(deps, paused)
+}
 
-        // transfer the token to the contract
-        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: collection_address.clone(),
-            msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
-                recipient: env.contract.address.to_string(),
-                token_id: token_id.to_string(),
-            })?,
-            funds: vec![],
-        }));
+pub fn execute_add_relayer(
+    deps: DepsMut,
+    info: MessageInfo,
+    relayer: String,
+) -> Result<Response, ContractError> {
+    // only controller can manage relayers
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
 
-        // query info of the token
-        let token_info_response: StdResult<Cw721NftInfoResponse<Extension>> =
-            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
-                contract_addr: collection_address.clone(),
-                msg: to_json_binary(&Cw721QueryMsg::NftInfo {
-                    token_id: token_id.to_string(),
-                })?,
-            }));
+    let relayer = deps.api.addr_validate(&relayer)?;
+    RELAYERS.save(deps.storage, &relayer, &Empty {})?;
 
-        // decide the token uri and extension
-        let (token_uri, _extension) = match token_info_response {
-            Ok(token_info) => {
-                // if the token_uri of mirrored data is not empty, then use it
-                if wrap_data.mirrored_data.base_uri.is_some() {
-                    (
-                        Some(format!(
-                            "{}{}.json",
-                            wrap_data.mirrored_data.base_uri.clone().unwrap(),
-                            token_id
-                        )),
-                        token_info.extension,
-                    )
-                } else {
-                    (token_info.token_uri, token_info.extension)
-                }
-            }
-            Err(_) => {
-                return Err(ContractError::CustomError {
-                    val: format!("Cannot query token info of {}", token_id),
-                });
-            }
-        };
+    Ok(Response::new().add_attributes([("method", "add_relayer"), ("relayer", relayer.as_str())]))
+}
 
-        // mint mirrored token to the sender
-        let mint_msg: Cw721BaseExecuteMsg<_, Extension> = Cw721BaseExecuteMsg::Mint {
-            owner: info.sender.to_string(),
-            token_id: token_id.to_string(),
-            token_uri,
-            extension: Empty::default(),
-        };
-        res = res.add_message(WasmMsg::Execute {
-            contract_addr: wrap_data.mirrored_collection.to_string(),
-            msg: to_json_binary(&mint_msg)?,
-            funds: vec![],
-        });
+pub fn execute_remove_relayer(
+    deps: DepsMut,
+    info: MessageInfo,
+    relayer: String,
+) -> Result<Response, ContractError> {
+    // only controller can manage relayers
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    Ok(res.add_attributes([
-        ("method", "wrap"),
-        ("collection_address", &collection_address),
-        ("token_ids", &token_ids.join(",")),
-    ]))
+    let relayer = deps.api.addr_validate(&relayer)?;
+    RELAYERS.remove(deps.storage, &relayer);
+
+    Ok(Response::new()
+        .add_attributes([("method", "remove_relayer"), ("relayer", relayer.as_str())]))
 }
 
-pub fn execute_unwrap(
+pub fn execute_set_collection_status(
     deps: DepsMut,
-    _env: Env,
     info: MessageInfo,
     collection_address: String,
-    token_ids: Vec<String>,
+    active: bool,
 ) -> Result<Response, ContractError> {
-    // if the collection is not registered, then return error
-    if !MIRRORED_COLLECTIONS.has(deps.storage, deps.api.addr_validate(&collection_address)?) {
-        return Err(ContractError::CollectionNotAllowed {});
+    // only controller can change status of collection
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    // if the mirrored collection status is not active, then return error
-    let wrap_data = WRAP_DATA.load(
-        deps.storage,
-        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?,
-    )?;
-    if !wrap_data.active {
-        return Err(ContractError::CollectionDeactivated {});
+    set_collection_status(deps, collection_address, active)
+}
+
+pub fn execute_update_collection_info(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection_address: String,
+    name: Option<String>,
+    symbol: Option<String>,
+) -> Result<Response, ContractError> {
+    // only controller can update the info of mirrored collections
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
     }
 
-    let mut res = Response::new();
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
 
-    for token_id in token_ids.iter() {
-        // if the token id are not owned by the sender, then return error
-        let owner_response: StdResult<OwnerOfResponse> =
+    // the mirrored collection must stay bridgeable by ics721
+    if let Some(policy) = &config.ics721_policy {
+        let contract_info: Cw721ContractInfoResponse =
             deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
                 contract_addr: collection_address.clone(),
-                msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
-                    token_id: token_id.to_string(),
-                    include_expired: None,
-                })?,
-            }));
-        match owner_response {
-            Ok(owner) => {
-                if owner.owner != info.sender {
-                    return Err(ContractError::NotOwnedBySender {
-                        val: token_id.to_string(),
-                    });
-                }
-            }
-            Err(_) => {
-                return Err(ContractError::NotOwnedBySender {
-                    val: token_id.to_string(),
-                });
-            }
-        }
+                msg: to_json_binary(&Cw721QueryMsg::ContractInfo {})?,
+            }))?;
+        validate_collection_info(
+            policy,
+            name.as_deref().unwrap_or(&contract_info.name),
+            symbol.as_deref().unwrap_or(&contract_info.symbol),
+        )?;
+    }
 
-        // burn the mirrored token
-        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: collection_address.clone(),
-            msg: to_json_binary(&Cw721ExecuteMsg::Burn {
-                token_id: token_id.to_string(),
-            })?,
-            funds: vec![],
-        }));
+    if name.is_some() {
+        wrap_data.mirrored_data.collection_name = name.clone();
+    }
+    if symbol.is_some() {
+        wrap_data.mirrored_data.collection_symbol = symbol.clone();
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
 
-        // transfer the original token to the sender
-        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
-            contract_addr: wrap_data.original_collection.to_string(),
-            msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
-                recipient: info.sender.to_string(),
-                token_id: token_id.to_string(),
-            })?,
-            funds: vec![],
-        }));
+    // the mirrored collection must be a mirrored-cw721 contract
+    let update_msg: Cw721BaseExecuteMsg<Extension, MirrorExecuteExt> =
+        Cw721BaseExecuteMsg::Extension {
+            msg: MirrorExecuteExt::UpdateCollectionInfo {
+                name: name.clone(),
+                symbol: symbol.clone(),
+            },
+        };
+
+    let mut res = Response::new().add_message(WasmMsg::Execute {
+        contract_addr: collection_address.clone(),
+        msg: to_json_binary(&update_msg)?,
+        funds: vec![],
+    });
+
+    if let Some(name) = name {
+        res = res.add_attribute("name", name);
+    }
+    if let Some(symbol) = symbol {
+        res = res.add_attribute("symbol", symbol);
     }
 
     Ok(res.add_attributes([
-        ("method", "unwrap"),
+        ("method", "update_collection_info"),
         ("collection_address", &collection_address),
-        ("token_ids", &token_ids.join(",")),
     ]))
 }
 
-pub fn query_controller(deps: Deps) -> StdResult<Addr> {
-    CONTROLLER.load(deps.storage)
+#[allow(clippy::too_many_arguments)]
+pub fn execute_update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw721_code_id: Option<u64>,
+    wrapper_as_mirror_admin: Option<bool>,
+    refresh_interval: Option<u64>,
+    ics721: Option<String>,
+    bridge_timeout: Option<u64>,
+    recovery_timelock: Option<u64>,
+) -> Result<Response, ContractError> {
+    // only controller can update config
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(cw721_code_id) = cw721_code_id {
+        config.cw721_code_id = cw721_code_id;
+    }
+    if let Some(wrapper_as_mirror_admin) = wrapper_as_mirror_admin {
+        config.wrapper_as_mirror_admin = wrapper_as_mirror_admin;
+    }
+    if let Some(refresh_interval) = refresh_interval {
+        config.refresh_interval = refresh_interval;
+    }
+    if let Some(ics721) = ics721 {
+        config.ics721 = Some(deps.api.addr_validate(&ics721)?);
+    }
+    if let Some(bridge_timeout) = bridge_timeout {
+        config.bridge_timeout = bridge_timeout;
+    }
+    if let Some(recovery_timelock) = recovery_timelock {
+        validate_recovery_timelock(recovery_timelock)?;
+        config.recovery_timelock = recovery_timelock;
+    }
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "update_config"),
+        ("cw721_code_id", &config.cw721_code_id.to_string()),
+        (
+            "wrapper_as_mirror_admin",
+            &config.wrapper_as_mirror_admin.to_string(),
+        ),
+        ("refresh_interval", &config.refresh_interval.to_string()),
+        ("bridge_timeout", &config.bridge_timeout.to_string()),
+        ("recovery_timelock", &config.recovery_timelock.to_string()),
+    ]))
+}
+
+pub fn execute_migrate_mirrors(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    msg: Binary,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Response, ContractError> {
+    // only controller can migrate mirrored collections
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+    let wrap_data_list = WRAP_DATA
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // the last index is used as start_after of the next page
+    let last_index = wrap_data_list.last().map(|(index, _)| index.to_string());
+
+    let mut res = Response::new();
+    let mut migrated: Vec<String> = vec![];
+
+    for (wrap_data_index, mut wrap_data) in wrap_data_list {
+        // only the mirrored collections administrated by the wrapper can be migrated
+        if wrap_data.admin.as_ref() != Some(&env.contract.address)
+            || wrap_data.mirrored_collection.as_str().is_empty()
+        {
+            continue;
+        }
+
+        res = res.add_message(WasmMsg::Migrate {
+            contract_addr: wrap_data.mirrored_collection.to_string(),
+            new_code_id: code_id,
+            msg: msg.clone(),
+        });
+
+        // record the new code id of the mirrored collection
+        wrap_data.code_id = code_id;
+        WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+        migrated.push(wrap_data.mirrored_collection.to_string());
+    }
+
+    // empty attribute values are rejected by the chain
+    if !migrated.is_empty() {
+        res = res.add_attribute("mirrored_collections", migrated.join(","));
+    }
+    if let Some(last_index) = last_index {
+        res = res.add_attribute("last_index", last_index);
+    }
+
+    Ok(res.add_attributes([
+        ("method", "migrate_mirrors"),
+        ("code_id", &code_id.to_string()),
+    ]))
+}
+
+pub fn execute_reveal(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    // only controller can reveal the mirrored collection
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    wrap_data.revealed = true;
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    // the already minted mirrored tokens are updated to the revealed token uri
+    let update_msgs =
+        update_token_metadata_msgs(deps.as_ref(), &env, wrap_data_index, &wrap_data, &token_ids)?;
+
+    let mut res = Response::new().add_messages(update_msgs).add_attributes([
+        ("method", "reveal"),
+        ("collection_address", &collection_address),
+    ]);
+    if !token_ids.is_empty() {
+        res = res.add_attribute("token_ids", token_ids.join(","));
+    }
+
+    Ok(res)
+}
+
+pub fn execute_refresh_metadata(
+    deps: DepsMut,
+    env: Env,
+    collection_address: String,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    let refresh_interval = CONFIG.load(deps.storage)?.refresh_interval;
+
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+
+    // each mirrored token can only be refreshed once per refresh interval
+    for token_id in token_ids.iter() {
+        if let Some(last_refreshed) =
+            LAST_REFRESHED.may_load(deps.storage, (wrap_data_index, token_id))?
+        {
+            if env.block.time < last_refreshed.plus_seconds(refresh_interval) {
+                return Err(ContractError::RefreshTooSoon {
+                    val: token_id.to_string(),
+                });
+            }
+        }
+        LAST_REFRESHED.save(deps.storage, (wrap_data_index, token_id), &env.block.time)?;
+    }
+
+    let update_msgs =
+        update_token_metadata_msgs(deps.as_ref(), &env, wrap_data_index, &wrap_data, &token_ids)?;
+
+    Ok(Response::new().add_messages(update_msgs).add_attributes([
+        ("method", "refresh_metadata"),
+        ("collection_address", &collection_address),
+        ("token_ids", &token_ids.join(",")),
+    ]))
+}
+
+pub fn update_controller(deps: DepsMut, controller: String) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.controller = deps.api.addr_validate(&controller)?;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new()
+        .add_attributes([("method", "update_controller"), ("controller", &controller)]))
+}
+
+pub fn set_paused(deps: DepsMut, paused: bool) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.paused = paused;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([("method", "set_paused"), ("paused", &paused.to_string())]))
+}
+
+pub fn set_collection_status(
+    deps: DepsMut,
+    collection_address: String,
+    active: bool,
+) -> Result<Response, ContractError> {
+    // the status is changed through the mirrored collection
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    wrap_data.active = active;
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "set_collection_status"),
+        ("collection_address", &collection_address),
+        ("active", &active.to_string()),
+    ]))
+}
+
+/// burn the mirrored tokens and release their original tokens from the escrow
+pub fn force_unwrap(
+    deps: DepsMut,
+    env: Env,
+    collection_address: String,
+    token_ids: Vec<String>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let mut res = Response::new();
+
+    for token_id in token_ids.iter() {
+        // burn the mirrored token, then transfer the original token to the recipient
+        let owner = query_owner_of(deps.as_ref(), &collection_address, token_id)?;
+        res = res.add_message(burn_mirrored_token_msg(
+            deps.as_ref(),
+            &env,
+            &collection_address,
+            &owner,
+            token_id,
+        )?);
+        res = res.add_message(release_original_token_msg(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            token_id,
+            &recipient,
+            None,
+        )?);
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    Ok(res.add_attributes([
+        ("method", "force_unwrap"),
+        ("collection_address", &collection_address),
+        ("token_ids", &token_ids.join(",")),
+        ("recipient", recipient.as_str()),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_wrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_ids: Vec<String>,
+    mirror_index: Option<u32>,
+    recipient: Option<String>,
+    owner: Option<String>,
+    then: Option<PostAction>,
+) -> Result<Response, ContractError> {
+    // if the contract is paused, then return error
+    let config = CONFIG.load(deps.storage)?;
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+    let ics721_policy = config.ics721_policy;
+
+    // only relayers can wrap for another owner
+    let relayed_owner = relayed_owner(deps.as_ref(), &info.sender, owner)?;
+
+    // the mirrored tokens of a post action are received by its contract
+    let recipient = match (&then, recipient) {
+        (Some(_), Some(_)) => return Err(ContractError::RecipientWithPostAction {}),
+        (Some(then), None) => Some(deps.api.addr_validate(&then.contract)?),
+        (None, recipient) => recipient
+            .map(|recipient| deps.api.addr_validate(&recipient))
+            .transpose()?,
+    };
+
+    // if the collection is not registered, then return error
+    let original_collection = deps.api.addr_validate(&collection_address)?;
+    let mirrors = ORIGINAL_COLLECTIONS
+        .may_load(deps.storage, original_collection.clone())?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    // if the target mirror does not exist, then return error
+    let mirror_index = mirror_index.unwrap_or(0);
+    let wrap_data_index = *mirrors
+        .get(mirror_index as usize)
+        .ok_or(ContractError::MirrorNotFound { val: mirror_index })?;
+
+    // if the original collection status is not active, then return error
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    if !wrap_data.active {
+        return Err(ContractError::CollectionDeactivated {});
+    }
+
+    // only a mirrored-cw721 collection stores the metadata of the original tokens
+    let mirrored_cw721 =
+        is_mirrored_cw721(deps.as_ref(), &env, wrap_data.mirrored_collection.as_str());
+
+    let mut res = Response::new();
+    let mut mirrored_token_ids: Vec<String> = vec![];
+    let mut recipients: Vec<String> = vec![];
+
+    for token_id in token_ids.iter() {
+        // if the sender is neither the owner nor approved by the owner, then return error
+        let owner = query_authorized_owner(
+            deps.as_ref(),
+            &collection_address,
+            token_id,
+            &info.sender,
+            relayed_owner.as_ref(),
+        )?;
+        let owner_addr = deps.api.addr_validate(&owner.owner)?;
+        let token_recipient = recipient.clone().unwrap_or_else(|| owner_addr.clone());
+
+        // a token can only be wrapped into one mirror at a time,
+        // a recovered token can be wrapped again as its stranded mirrored token is burnt
+        if let Some(Escrow {
+            status: EscrowStatus::Escrowed,
+            ..
+        }) = ESCROWS.may_load(deps.storage, (&original_collection, token_id))?
+        {
+            return Err(ContractError::AlreadyWrapped {
+                val: token_id.to_string(),
+            });
+        }
+        ESCROWS.save(
+            deps.storage,
+            (&original_collection, token_id),
+            &Escrow {
+                wrap_data_index,
+                owner: owner_addr,
+                recipient: token_recipient.clone(),
+                status: EscrowStatus::Escrowed,
+            },
+        )?;
+        wrap_data.escrowed += 1;
+
+        let mirrored_token_id =
+            mirrored_token_id(deps.storage, wrap_data_index, &mut wrap_data, token_id)?;
+
+        // the mirrored token must be bridgeable by ics721
+        if let Some(policy) = &ics721_policy {
+            validate_token_id(policy, &mirrored_token_id)?;
+        }
+
+        // transfer the token to the contract
+        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: collection_address.clone(),
+            msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                recipient: env.contract.address.to_string(),
+                token_id: token_id.to_string(),
+            })?,
+            funds: vec![],
+        }));
+
+        // query info of the token
+        let token_info_response: StdResult<Cw721NftInfoResponse<Extension>> =
+            deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: collection_address.clone(),
+                msg: to_json_binary(&Cw721QueryMsg::NftInfo {
+                    token_id: token_id.to_string(),
+                })?,
+            }));
+
+        // decide the token uri and extension
+        let (token_uri, extension) = match token_info_response {
+            Ok(token_info) => (
+                mirrored_token_uri(
+                    &wrap_data.mirrored_data,
+                    is_revealed(&wrap_data.mirrored_data, wrap_data.revealed, env.block.time),
+                    &mirrored_token_id,
+                    token_id,
+                    token_info.token_uri,
+                )?,
+                token_info.extension,
+            ),
+            Err(_) => {
+                return Err(ContractError::CustomError {
+                    val: format!("Cannot query token info of {}", token_id),
+                });
+            }
+        };
+
+        // mint mirrored token with the metadata of the original token to the recipient,
+        // or to the wrapper which sends it to the contract of the post action
+        let mint_owner = match &then {
+            Some(_) => env.contract.address.to_string(),
+            None => token_recipient.to_string(),
+        };
+        let mint_msg = if mirrored_cw721 {
+            to_json_binary(&Cw721BaseExecuteMsg::<Extension, Empty>::Mint {
+                owner: mint_owner,
+                token_id: mirrored_token_id.clone(),
+                token_uri,
+                extension,
+            })?
+        } else {
+            to_json_binary(&Cw721BaseExecuteMsg::<Empty, Empty>::Mint {
+                owner: mint_owner,
+                token_id: mirrored_token_id.clone(),
+                token_uri,
+                extension: Empty::default(),
+            })?
+        };
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: wrap_data.mirrored_collection.to_string(),
+            msg: mint_msg,
+            funds: vec![],
+        });
+
+        if let Some(then) = &then {
+            res = res.add_message(WasmMsg::Execute {
+                contract_addr: wrap_data.mirrored_collection.to_string(),
+                msg: to_json_binary(&Cw721ExecuteMsg::SendNft {
+                    contract: then.contract.clone(),
+                    token_id: mirrored_token_id.clone(),
+                    msg: post_action_msg(&owner.owner, then)?,
+                })?,
+                funds: vec![],
+            });
+        }
+
+        BRIDGE_STATES.save(
+            deps.storage,
+            (wrap_data_index, &mirrored_token_id),
+            &BridgeState::Wrapped,
+        )?;
+
+        mirrored_token_ids.push(mirrored_token_id);
+        recipients.push(token_recipient.to_string());
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    // tag the operations of relayers
+    if relayed_owner.is_some() {
+        res = res.add_attribute("relayer", info.sender.as_str());
+    }
+
+    Ok(res.add_attributes([
+        ("method", "wrap"),
+        ("collection_address", &collection_address),
+        (
+            "mirrored_collection",
+            wrap_data.mirrored_collection.as_str(),
+        ),
+        ("token_ids", &token_ids.join(",")),
+        ("mirrored_token_ids", &mirrored_token_ids.join(",")),
+        ("recipients", &recipients.join(",")),
+    ]))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute_unwrap(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_ids: Vec<String>,
+    recipient: Option<String>,
+    owner: Option<String>,
+    then: Option<PostAction>,
+) -> Result<Response, ContractError> {
+    // if the contract is paused, then return error
+    if CONFIG.load(deps.storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    // only relayers can unwrap for another owner
+    let relayed_owner = relayed_owner(deps.as_ref(), &info.sender, owner)?;
+
+    // the original tokens of a post action are received by its contract
+    let recipient = match (&then, recipient) {
+        (Some(_), Some(_)) => return Err(ContractError::RecipientWithPostAction {}),
+        (Some(then), None) => Some(deps.api.addr_validate(&then.contract)?),
+        (None, recipient) => recipient
+            .map(|recipient| deps.api.addr_validate(&recipient))
+            .transpose()?,
+    };
+
+    // if the collection is not registered, then return error
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    // if the mirrored collection status is not active, then return error
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    if !wrap_data.active {
+        return Err(ContractError::CollectionDeactivated {});
+    }
+
+    let mut res = Response::new();
+    let mut recipients: Vec<String> = vec![];
+
+    for token_id in token_ids.iter() {
+        // if the sender is neither the owner nor approved by the owner, then return error
+        let owner = query_authorized_owner(
+            deps.as_ref(),
+            &collection_address,
+            token_id,
+            &info.sender,
+            relayed_owner.as_ref(),
+        )?;
+        let token_recipient = match &recipient {
+            Some(recipient) => recipient.clone(),
+            None => deps.api.addr_validate(&owner.owner)?,
+        };
+
+        // burn the mirrored token, then transfer the original token to the recipient
+        res = res.add_message(burn_mirrored_token_msg(
+            deps.as_ref(),
+            &env,
+            &collection_address,
+            &owner,
+            token_id,
+        )?);
+        res = res.add_message(release_original_token_msg(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            token_id,
+            &token_recipient,
+            then.as_ref()
+                .map(|then| post_action_msg(&owner.owner, then))
+                .transpose()?
+                .as_ref(),
+        )?);
+        recipients.push(token_recipient.to_string());
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    // tag the operations of relayers
+    if relayed_owner.is_some() {
+        res = res.add_attribute("relayer", info.sender.as_str());
+    }
+
+    Ok(res.add_attributes([
+        ("method", "unwrap"),
+        ("collection_address", &collection_address),
+        ("token_ids", &token_ids.join(",")),
+        ("recipients", &recipients.join(",")),
+    ]))
+}
+
+/// unwrap the mirrored tokens and send the original tokens to the ics721 contract,
+/// which bridges them to the receiver on the counterparty chain
+#[allow(clippy::too_many_arguments)]
+pub fn execute_unwrap_and_bridge(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_ids: Vec<String>,
+    channel_id: String,
+    receiver: String,
+    timeout_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    // if the contract is paused, then return error
+    let config = CONFIG.load(deps.storage)?;
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    // if the ics721 contract is not configured, then return error
+    let ics721 = config.ics721.ok_or(ContractError::Ics721NotConfigured {})?;
+
+    // if the collection is not registered, then return error
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    // if the mirrored collection status is not active, then return error
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    if !wrap_data.active {
+        return Err(ContractError::CollectionDeactivated {});
+    }
+
+    // only the channels registered by the controller can be used
+    let channel = BRIDGE_CHANNELS
+        .may_load(deps.storage, &channel_id)?
+        .ok_or_else(|| ContractError::UnknownChannel {
+            val: channel_id.clone(),
+        })?;
+
+    // the receiver must be an address of the counterparty chain
+    let receiver = validate_receiver(&channel, &receiver, &env.contract.address)?;
+
+    // the original tokens are sent to the ics721 contract with the transfer message
+    let timeout = env
+        .block
+        .time
+        .plus_seconds(timeout_seconds.unwrap_or(config.bridge_timeout));
+    let bridge_msg = to_json_binary(&IbcOutgoingMsg {
+        receiver: receiver.clone(),
+        channel_id: channel_id.clone(),
+        timeout: IbcTimeout::with_timestamp(timeout),
+        memo: None,
+    })?;
+
+    let mut res = Response::new();
+
+    for token_id in token_ids.iter() {
+        // if the sender is neither the owner nor approved by the owner, then return error
+        let owner = query_authorized_owner(
+            deps.as_ref(),
+            &collection_address,
+            token_id,
+            &info.sender,
+            None,
+        )?;
+
+        // burn the mirrored token, then send the original token to the ics721 contract
+        res = res.add_message(burn_mirrored_token_msg(
+            deps.as_ref(),
+            &env,
+            &collection_address,
+            &owner,
+            token_id,
+        )?);
+        res = res.add_message(release_original_token_msg(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            token_id,
+            &ics721,
+            Some(&bridge_msg),
+        )?);
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    Ok(res.add_attributes([
+        ("method", "unwrap_and_bridge"),
+        ("collection_address", &collection_address),
+        ("token_ids", &token_ids.join(",")),
+        ("channel_id", &channel_id),
+        ("receiver", &receiver),
+    ]))
+}
+
+/// handle a mirrored token sent to the wrapper by `SendNft`
+pub fn execute_receive_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    receive_msg: Cw721ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // if the contract is paused, then return error
+    if CONFIG.load(deps.storage)?.paused {
+        return Err(ContractError::Paused {});
+    }
+
+    // the sender of the message is the mirrored collection
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, info.sender.clone())?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    // if the mirrored collection status is not active, then return error
+    let wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    if !wrap_data.active {
+        return Err(ContractError::CollectionDeactivated {});
+    }
+
+    let sender = deps.api.addr_validate(&receive_msg.sender)?;
+    let token_id = receive_msg.token_id;
+
+    match from_json(&receive_msg.msg)? {
+        ReceiveMsg::Unwrap {} => {
+            receive_unwrap(deps, env, wrap_data_index, wrap_data, sender, token_id)
+        }
+        ReceiveMsg::Bridge {
+            channel_id,
+            receiver,
+            timeout_seconds,
+        } => receive_bridge(
+            deps,
+            env,
+            wrap_data_index,
+            wrap_data,
+            sender,
+            token_id,
+            channel_id,
+            receiver,
+            timeout_seconds,
+        ),
+    }
+}
+
+/// unwrap a mirrored token owned by the wrapper,
+/// the wrapper owns the token so it can burn it without any approval
+fn receive_unwrap(
+    deps: DepsMut,
+    env: Env,
+    wrap_data_index: u64,
+    mut wrap_data: WrapData,
+    sender: Addr,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // burn the mirrored token, then transfer the original token to the sender
+    let burn_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: wrap_data.mirrored_collection.to_string(),
+        msg: to_json_binary(&Cw721ExecuteMsg::Burn {
+            token_id: token_id.clone(),
+        })?,
+        funds: vec![],
+    });
+    let release_msg = release_original_token_msg(
+        deps.storage,
+        env.block.time,
+        wrap_data_index,
+        &mut wrap_data,
+        &token_id,
+        &sender,
+        None,
+    )?;
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    Ok(Response::new()
+        .add_message(burn_msg)
+        .add_message(release_msg)
+        .add_attributes([
+            ("method", "receive_nft"),
+            ("collection_address", wrap_data.mirrored_collection.as_str()),
+            ("token_id", &token_id),
+            ("sender", sender.as_str()),
+        ]))
+}
+
+/// bridge a mirrored token owned by the wrapper through the ics721 contract,
+/// the ics721 contract calls back the wrapper once the transfer is acknowledged or timed out
+#[allow(clippy::too_many_arguments)]
+fn receive_bridge(
+    deps: DepsMut,
+    env: Env,
+    wrap_data_index: u64,
+    mut wrap_data: WrapData,
+    sender: Addr,
+    token_id: String,
+    channel_id: String,
+    receiver: String,
+    timeout_seconds: Option<u64>,
+) -> Result<Response, ContractError> {
+    // if the ics721 contract is not configured, then return error
+    let config = CONFIG.load(deps.storage)?;
+    let ics721 = config.ics721.ok_or(ContractError::Ics721NotConfigured {})?;
+
+    // only the channels registered by the controller can be used
+    let channel = BRIDGE_CHANNELS
+        .may_load(deps.storage, &channel_id)?
+        .ok_or_else(|| ContractError::UnknownChannel {
+            val: channel_id.clone(),
+        })?;
+
+    // the receiver must be an address of the counterparty chain
+    let receiver = validate_receiver(&channel, &receiver, &env.contract.address)?;
+
+    // ask the ics721 contract to call back the wrapper with the wrap data index
+    let memo = Ics721Memo {
+        callbacks: Some(Ics721Callbacks {
+            ack_callback_data: Some(to_json_binary(&wrap_data_index)?),
+            ack_callback_addr: Some(env.contract.address.to_string()),
+            receive_callback_data: None,
+            receive_callback_addr: None,
+        }),
+    };
+    let timeout = env
+        .block
+        .time
+        .plus_seconds(timeout_seconds.unwrap_or(config.bridge_timeout));
+    let bridge_msg = to_json_binary(&IbcOutgoingMsg {
+        receiver: receiver.clone(),
+        channel_id: channel_id.clone(),
+        timeout: IbcTimeout::with_timestamp(timeout),
+        memo: Some(to_json_string(&memo)?),
+    })?;
+
+    // a token bridged before is back on this chain as the wrapper received it
+    if let Some(state) = BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, &token_id))? {
+        settle_bridged_token(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            &token_id,
+            &state,
+        )?;
+    }
+
+    // the channel cannot be used once its limit is reached in the current window
+    let window_start = match RATE_LIMITS.may_load(deps.storage, &channel_id)? {
+        Some(rate_limit) => {
+            let mut rate_limit = rate_limit.at(env.block.time);
+            if rate_limit.used >= rate_limit.max_tokens {
+                return Err(ContractError::RateLimitExceeded { val: channel_id });
+            }
+            rate_limit.used += 1;
+            RATE_LIMITS.save(deps.storage, &channel_id, &rate_limit)?;
+            Some(rate_limit.window_start)
+        }
+        None => None,
+    };
+
+    BRIDGE_STATES.save(
+        deps.storage,
+        (wrap_data_index, &token_id),
+        &BridgeState::InFlight {
+            channel: channel_id.clone(),
+            chain: channel.chain_id,
+            owner: sender.clone(),
+            window_start,
+        },
+    )?;
+    wrap_data.bridged_out += 1;
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: wrap_data.mirrored_collection.to_string(),
+            msg: to_json_binary(&Cw721ExecuteMsg::SendNft {
+                contract: ics721.to_string(),
+                token_id: token_id.clone(),
+                msg: bridge_msg,
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes([
+            ("method", "bridge"),
+            ("collection_address", wrap_data.mirrored_collection.as_str()),
+            ("token_id", &token_id),
+            ("sender", sender.as_str()),
+            ("channel_id", &channel_id),
+            ("receiver", &receiver),
+        ]))
+}
+
+/// update the bridge states of mirrored tokens once their transfer is acknowledged or timed out,
+/// the tokens of a failed transfer are refunded to the wrapper and returned to their owners
+pub fn execute_ics721_ack_callback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    ack: Ics721AckCallbackMsg,
+) -> Result<Response, ContractError> {
+    // only the ics721 contract can call back
+    if Some(info.sender) != CONFIG.load(deps.storage)?.ics721 {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let wrap_data_index: u64 = from_json(&ack.msg)?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    if wrap_data.mirrored_collection != ack.nft_contract {
+        return Err(ContractError::CollectionNotAllowed {});
+    }
+
+    let mut res = Response::new();
+
+    for token_id in ack.original_packet.token_ids.iter() {
+        let (channel, chain, owner, window_start) =
+            match BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, token_id))? {
+                Some(BridgeState::InFlight {
+                    channel,
+                    chain,
+                    owner,
+                    window_start,
+                }) => (channel, chain, owner, window_start),
+                _ => continue,
+            };
+
+        // the channel may have been removed while the token was in flight
+        let state = match &ack.status {
+            Ics721Status::Success => BridgeState::Bridged {
+                chain,
+                channel,
+                window_start,
+            },
+            Ics721Status::Failed(_) => {
+                // return the refunded token to its owner
+                res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+                    contract_addr: wrap_data.mirrored_collection.to_string(),
+                    msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+                        recipient: owner.to_string(),
+                        token_id: token_id.to_string(),
+                    })?,
+                    funds: vec![],
+                }));
+                settle_bridged_token(
+                    deps.storage,
+                    env.block.time,
+                    wrap_data_index,
+                    &mut wrap_data,
+                    token_id,
+                    &BridgeState::InFlight {
+                        channel,
+                        chain,
+                        owner,
+                        window_start,
+                    },
+                )?;
+                BridgeState::Returned
+            }
+        };
+        BRIDGE_STATES.save(deps.storage, (wrap_data_index, token_id), &state)?;
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    let status = match &ack.status {
+        Ics721Status::Success => "success".to_string(),
+        Ics721Status::Failed(error) => format!("failed: {}", error),
+    };
+
+    Ok(res.add_attributes([
+        ("method", "ics721_ack_callback"),
+        ("collection_address", wrap_data.mirrored_collection.as_str()),
+        ("token_ids", &ack.original_packet.token_ids.join(",")),
+        ("status", &status),
+    ]))
+}
+
+/// the bridged tokens are sent back through the ics721 contract without any callback to the wrapper,
+/// so a token is known to be back once the ics721 contract no longer holds it
+pub fn execute_sync_bridge_states(
+    deps: DepsMut,
+    env: Env,
+    collection_address: String,
+    token_ids: Vec<String>,
+) -> Result<Response, ContractError> {
+    // if the ics721 contract is not configured, then return error
+    let ics721 = CONFIG
+        .load(deps.storage)?
+        .ics721
+        .ok_or(ContractError::Ics721NotConfigured {})?;
+
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+
+    let mut returned: Vec<String> = vec![];
+
+    for token_id in token_ids.iter() {
+        let state = match BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, token_id))? {
+            Some(state @ BridgeState::Bridged { .. }) => state,
+            _ => continue,
+        };
+        if query_owner_of(deps.as_ref(), &collection_address, token_id)?.owner == ics721 {
+            continue;
+        }
+
+        settle_bridged_token(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            token_id,
+            &state,
+        )?;
+        BRIDGE_STATES.save(
+            deps.storage,
+            (wrap_data_index, token_id),
+            &BridgeState::Returned,
+        )?;
+        returned.push(token_id.to_string());
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    let mut res = Response::new();
+    if !returned.is_empty() {
+        res = res.add_attribute("returned_token_ids", returned.join(","));
+    }
+
+    Ok(res.add_attributes([
+        ("method", "sync_bridge_states"),
+        ("collection_address", &collection_address),
+    ]))
+}
+
+pub fn execute_set_bridge_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+    chain_id: String,
+    bech32_prefix: Option<String>,
+    convert_local_receiver: Option<bool>,
+) -> Result<Response, ContractError> {
+    // only controller can manage bridge channels
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    BRIDGE_CHANNELS.save(
+        deps.storage,
+        &channel_id,
+        &ChannelInfo {
+            chain_id: chain_id.clone(),
+            bech32_prefix: bech32_prefix.clone(),
+            convert_local_receiver: convert_local_receiver.unwrap_or(false),
+        },
+    )?;
+
+    let mut res = Response::new();
+    if let Some(bech32_prefix) = bech32_prefix {
+        res = res.add_attribute("bech32_prefix", bech32_prefix);
+    }
+
+    Ok(res.add_attributes([
+        ("method", "set_bridge_channel"),
+        ("channel_id", &channel_id),
+        ("chain_id", &chain_id),
+    ]))
+}
+
+pub fn execute_set_rate_limit(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    channel_id: String,
+    max_tokens: u64,
+    window_seconds: u64,
+) -> Result<Response, ContractError> {
+    // only controller can manage rate limits
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // an empty window would reset the usage on every transfer
+    if window_seconds == 0 {
+        return Err(ContractError::InvalidRateLimitWindow {
+            val: window_seconds,
+        });
+    }
+
+    // keep the usage of the current window
+    let rate_limit = match RATE_LIMITS.may_load(deps.storage, &channel_id)? {
+        Some(rate_limit) => RateLimit {
+            max_tokens,
+            window_seconds,
+            ..rate_limit
+        }
+        .at(env.block.time),
+        None => RateLimit {
+            max_tokens,
+            window_seconds,
+            window_start: env.block.time,
+            used: 0,
+        },
+    };
+    RATE_LIMITS.save(deps.storage, &channel_id, &rate_limit)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "set_rate_limit"),
+        ("channel_id", &channel_id),
+        ("max_tokens", &max_tokens.to_string()),
+        ("window_seconds", &window_seconds.to_string()),
+    ]))
+}
+
+pub fn execute_remove_rate_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+) -> Result<Response, ContractError> {
+    // only controller can manage rate limits
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    RATE_LIMITS.remove(deps.storage, &channel_id);
+
+    Ok(Response::new()
+        .add_attributes([("method", "remove_rate_limit"), ("channel_id", &channel_id)]))
+}
+
+pub fn execute_propose_recovery(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    collection_address: String,
+    token_id: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    // only controller can propose a recovery
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+
+    // only the mirrored tokens sent to other chains and still held by the ics721 contract
+    // can be stranded
+    query_stranded_owner(
+        deps.as_ref(),
+        config.ics721.as_ref(),
+        wrap_data_index,
+        &collection_address,
+        &token_id,
+    )?;
+
+    let recovery = Recovery {
+        recipient: deps.api.addr_validate(&recipient)?,
+        executable_at: env.block.time.plus_seconds(config.recovery_timelock),
+    };
+    RECOVERIES.save(deps.storage, (wrap_data_index, &token_id), &recovery)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "propose_recovery"),
+        ("collection_address", &collection_address),
+        ("token_id", &token_id),
+        ("recipient", recovery.recipient.as_str()),
+        (
+            "executable_at",
+            &recovery.executable_at.seconds().to_string(),
+        ),
+    ]))
+}
+
+/// anyone can execute a recovery once its timelock is over
+pub fn execute_execute_recovery(
+    deps: DepsMut,
+    env: Env,
+    collection_address: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+
+    let recovery = RECOVERIES
+        .may_load(deps.storage, (wrap_data_index, &token_id))?
+        .ok_or_else(|| ContractError::RecoveryNotFound {
+            val: token_id.clone(),
+        })?;
+    if env.block.time < recovery.executable_at {
+        return Err(ContractError::RecoveryTimelocked { val: token_id });
+    }
+    RECOVERIES.remove(deps.storage, (wrap_data_index, &token_id));
+
+    // the token may have come back during the timelock
+    let owner = query_stranded_owner(
+        deps.as_ref(),
+        CONFIG.load(deps.storage)?.ics721.as_ref(),
+        wrap_data_index,
+        &collection_address,
+        &token_id,
+    )?;
+
+    // burn the stranded mirrored token held by the ics721 contract,
+    // so it cannot be unwrapped if it comes back
+    let burn_msg =
+        burn_mirrored_token_msg(deps.as_ref(), &env, &collection_address, &owner, &token_id)?;
+
+    // the mirrored token is no longer on another chain
+    if let Some(state) = BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, &token_id))? {
+        settle_bridged_token(
+            deps.storage,
+            env.block.time,
+            wrap_data_index,
+            &mut wrap_data,
+            &token_id,
+            &state,
+        )?;
+    }
+    BRIDGE_STATES.remove(deps.storage, (wrap_data_index, &token_id))?;
+
+    // mark the escrow of the original token as recovered
+    let original_token_id = original_token_id(deps.storage, wrap_data_index, &token_id)?;
+    let key = (&wrap_data.original_collection, original_token_id.as_str());
+    if let Some(mut escrow) = ESCROWS.may_load(deps.storage, key)? {
+        if escrow.status == EscrowStatus::Recovered {
+            return Err(ContractError::AlreadyRecovered {
+                val: original_token_id,
+            });
+        }
+        escrow.status = EscrowStatus::Recovered;
+        ESCROWS.save(deps.storage, key, &escrow)?;
+        wrap_data.escrowed = wrap_data.escrowed.saturating_sub(1);
+    }
+    WRAP_DATA.save(deps.storage, wrap_data_index, &wrap_data)?;
+
+    // release the original token to the recipient
+    let release_msg = CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: wrap_data.original_collection.to_string(),
+        msg: to_json_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recovery.recipient.to_string(),
+            token_id: original_token_id.clone(),
+        })?,
+        funds: vec![],
+    });
+
+    Ok(Response::new()
+        .add_message(burn_msg)
+        .add_message(release_msg)
+        .add_attributes([
+            ("method", "execute_recovery"),
+            ("collection_address", &collection_address),
+            ("token_id", &token_id),
+            ("original_token_id", &original_token_id),
+            ("recipient", recovery.recipient.as_str()),
+        ]))
+}
+
+pub fn execute_cancel_recovery(
+    deps: DepsMut,
+    info: MessageInfo,
+    collection_address: String,
+    token_id: String,
+) -> Result<Response, ContractError> {
+    // only controller can cancel a recovery
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    // if the collection is not registered, then return error
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or(ContractError::CollectionNotAllowed {})?;
+    if !RECOVERIES.has(deps.storage, (wrap_data_index, &token_id)) {
+        return Err(ContractError::RecoveryNotFound { val: token_id });
+    }
+    RECOVERIES.remove(deps.storage, (wrap_data_index, &token_id));
+
+    Ok(Response::new().add_attributes([
+        ("method", "cancel_recovery"),
+        ("collection_address", &collection_address),
+        ("token_id", &token_id),
+    ]))
+}
+
+pub fn execute_set_ics721_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    policy: Option<Ics721Policy>,
+) -> Result<Response, ContractError> {
+    // only controller can set the ics721 compatibility policy
+    let mut config = CONFIG.load(deps.storage)?;
+    if info.sender != config.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    config.ics721_policy = policy;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes([
+        ("method", "set_ics721_policy"),
+        ("enabled", &config.ics721_policy.is_some().to_string()),
+    ]))
+}
+
+pub fn execute_remove_bridge_channel(
+    deps: DepsMut,
+    info: MessageInfo,
+    channel_id: String,
+) -> Result<Response, ContractError> {
+    // only controller can manage bridge channels
+    if info.sender != CONFIG.load(deps.storage)?.controller {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    BRIDGE_CHANNELS.remove(deps.storage, &channel_id);
+
+    Ok(Response::new().add_attributes([
+        ("method", "remove_bridge_channel"),
+        ("channel_id", &channel_id),
+    ]))
+}
+
+/// the owner of a token, the sender must be the owner, approved for the token
+/// or an operator of the owner, also when it is a relayer acting for the given owner
+fn query_authorized_owner(
+    deps: Deps,
+    collection_address: &str,
+    token_id: &str,
+    sender: &Addr,
+    relayed_owner: Option<&Addr>,
+) -> Result<OwnerOfResponse, ContractError> {
+    let owner: OwnerOfResponse = deps
+        .querier
+        .query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: collection_address.to_string(),
+            msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
+                token_id: token_id.to_string(),
+                include_expired: None,
+            })?,
+        }))
+        .map_err(|_| ContractError::NotOwnedBySender {
+            val: token_id.to_string(),
+        })?;
+
+    // a relayer acts for the owner of the token only,
+    // the approval of the wrapper is not enough as anyone can ask the wrapper to move a token
+    if let Some(relayed_owner) = relayed_owner {
+        if owner.owner != relayed_owner.as_str() {
+            return Err(ContractError::NotOwnedBySender {
+                val: token_id.to_string(),
+            });
+        }
+    }
+
+    if owner.owner == sender.as_str()
+        || is_approved(deps, collection_address, &owner, sender.as_str())?
+    {
+        return Ok(owner);
+    }
+
+    Err(ContractError::NotOwnedBySender {
+        val: token_id.to_string(),
+    })
+}
+
+/// the recovery timelock leaves the holders of stranded tokens time to react to a proposal,
+/// so it cannot be shorter than the default one
+fn validate_recovery_timelock(recovery_timelock: u64) -> Result<(), ContractError> {
+    if recovery_timelock < DEFAULT_RECOVERY_TIMELOCK {
+        return Err(ContractError::RecoveryTimelockTooShort {
+            val: recovery_timelock,
+            min: DEFAULT_RECOVERY_TIMELOCK,
+        });
+    }
+
+    Ok(())
+}
+
+/// the owner of a mirrored token stranded on another chain,
+/// the token must be sent through the ics721 contract and still be held by it
+fn query_stranded_owner(
+    deps: Deps,
+    ics721: Option<&Addr>,
+    wrap_data_index: u64,
+    collection_address: &str,
+    token_id: &str,
+) -> Result<OwnerOfResponse, ContractError> {
+    match BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, token_id))? {
+        Some(BridgeState::InFlight { .. }) | Some(BridgeState::Bridged { .. }) => {}
+        _ => {
+            return Err(ContractError::NotStranded {
+                val: token_id.to_string(),
+            })
+        }
+    }
+
+    // the bridge state is not updated when a token comes back until it is synced
+    let owner = query_owner_of(deps, collection_address, token_id)?;
+    if ics721.map(Addr::as_str) != Some(owner.owner.as_str()) {
+        return Err(ContractError::NotStranded {
+            val: token_id.to_string(),
+        });
+    }
+
+    Ok(owner)
+}
+
+/// the owner of a token
+fn query_owner_of(
+    deps: Deps,
+    collection_address: &str,
+    token_id: &str,
+) -> StdResult<OwnerOfResponse> {
+    deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: collection_address.to_string(),
+        msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
+            token_id: token_id.to_string(),
+            include_expired: None,
+        })?,
+    }))
+}
+
+/// whether the spender is approved for the token or is an operator of the owner
+fn is_approved(
+    deps: Deps,
+    collection_address: &str,
+    owner: &OwnerOfResponse,
+    spender: &str,
+) -> Result<bool, ContractError> {
+    Ok(owner
+        .approvals
+        .iter()
+        .any(|approval| approval.spender == spender)
+        || is_operator(deps, collection_address, &owner.owner, spender)?)
+}
+
+/// the owner a relayer acts for, only the registered relayers can act for another owner
+fn relayed_owner(
+    deps: Deps,
+    sender: &Addr,
+    owner: Option<String>,
+) -> Result<Option<Addr>, ContractError> {
+    match owner {
+        Some(owner) => {
+            if !RELAYERS.has(deps.storage, sender) {
+                return Err(ContractError::Unauthorized {});
+            }
+            Ok(Some(deps.api.addr_validate(&owner)?))
+        }
+        None => Ok(None),
+    }
+}
+
+/// whether the operator is approved for all tokens of the owner in the collection
+fn is_operator(
+    deps: Deps,
+    collection_address: &str,
+    owner: &str,
+    operator: &str,
+) -> Result<bool, ContractError> {
+    // cw721-base returns an error if the operator is not approved
+    let operator_response: StdResult<OperatorResponse> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: collection_address.to_string(),
+            msg: to_json_binary(&Cw721QueryMsg::Operator {
+                owner: owner.to_string(),
+                operator: operator.to_string(),
+                include_expired: None,
+            })?,
+        }));
+
+    Ok(operator_response.is_ok())
+}
+
+/// whether the collection is a mirrored-cw721 contract instantiated by this wrapper
+fn is_mirrored_cw721(deps: Deps, env: &Env, collection_address: &str) -> bool {
+    let mirror_info: StdResult<MirrorInfo> = to_json_binary(&MirroredCw721QueryMsg::Extension {
+        msg: MirrorQueryExt::MirrorInfo {},
+    })
+    .and_then(|msg| {
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: collection_address.to_string(),
+            msg,
+        }))
+    });
+
+    matches!(mirror_info, Ok(mirror_info) if mirror_info.wrapper == env.contract.address)
+}
+
+/// the message burning a mirrored token owned by someone else,
+/// a mirrored-cw721 collection lets the wrapper burn it directly,
+/// other collections require the wrapper to be approved by the owner
+fn burn_mirrored_token_msg(
+    deps: Deps,
+    env: &Env,
+    collection_address: &str,
+    owner: &OwnerOfResponse,
+    token_id: &str,
+) -> Result<CosmosMsg, ContractError> {
+    let msg = if is_mirrored_cw721(deps, env, collection_address) {
+        let burn_msg: Cw721BaseExecuteMsg<Extension, MirrorExecuteExt> =
+            Cw721BaseExecuteMsg::Extension {
+                msg: MirrorExecuteExt::BurnForUnwrap {
+                    token_id: token_id.to_string(),
+                },
+            };
+        to_json_binary(&burn_msg)?
+    } else {
+        // the wrapper must be approved on the token or be an operator of the owner
+        if !is_approved(
+            deps,
+            collection_address,
+            owner,
+            env.contract.address.as_str(),
+        )? {
+            return Err(ContractError::WrapperNotApproved {
+                val: token_id.to_string(),
+            });
+        }
+
+        to_json_binary(&Cw721ExecuteMsg::Burn {
+            token_id: token_id.to_string(),
+        })?
+    };
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: collection_address.to_string(),
+        msg,
+        funds: vec![],
+    }))
+}
+
+/// release the escrow of the original token of a mirrored token
+/// and return the message transferring it to the recipient,
+/// the token is sent with `SendNft` if a message for the recipient contract is given
+/// replace the placeholders of an instantiate message template in a single pass,
+/// so the replaced values are never scanned for placeholders again
+fn render_instantiate_template(
+    template: &str,
+    placeholders: &[(&str, String)],
+) -> Result<String, ContractError> {
+    // the wrapper must be the minter of the mirrored collection
+    if !template.contains("{minter}") {
+        return Err(ContractError::InvalidInstantiateTemplate {
+            val: template.to_string(),
+        });
+    }
+
+    let mut msg = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        msg.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match placeholders
+            .iter()
+            .find(|(placeholder, _)| rest.starts_with(placeholder))
+        {
+            Some((placeholder, value)) => {
+                msg.push_str(value);
+                rest = &rest[placeholder.len()..];
+            }
+            None => {
+                msg.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    msg.push_str(rest);
+    Ok(msg)
+}
+
+/// the message sent with the tokens to the contract of a post action on behalf of their owner
+fn post_action_msg(beneficiary: &str, then: &PostAction) -> StdResult<Binary> {
+    to_json_binary(&PostActionMsg {
+        beneficiary: beneficiary.to_string(),
+        msg: then.msg.clone(),
+    })
+}
+
+fn release_original_token_msg(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    wrap_data_index: u64,
+    wrap_data: &mut WrapData,
+    mirrored_token_id: &str,
+    recipient: &Addr,
+    send_msg: Option<&Binary>,
+) -> Result<CosmosMsg, ContractError> {
+    let original_token_id = original_token_id(storage, wrap_data_index, mirrored_token_id)?;
+    release_escrow(storage, wrap_data_index, wrap_data, &original_token_id)?;
+
+    // a mirrored token bridged before is burnt on this chain, so it is back or recovered
+    if let Some(state) = BRIDGE_STATES.may_load(storage, (wrap_data_index, mirrored_token_id))? {
+        settle_bridged_token(
+            storage,
+            now,
+            wrap_data_index,
+            wrap_data,
+            mirrored_token_id,
+            &state,
+        )?;
+    }
+    BRIDGE_STATES.remove(storage, (wrap_data_index, mirrored_token_id))?;
+
+    let msg = match send_msg {
+        Some(send_msg) => to_json_binary(&Cw721ExecuteMsg::SendNft {
+            contract: recipient.to_string(),
+            token_id: original_token_id,
+            msg: send_msg.clone(),
+        })?,
+        None => to_json_binary(&Cw721ExecuteMsg::TransferNft {
+            recipient: recipient.to_string(),
+            token_id: original_token_id,
+        })?,
+    };
+
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: wrap_data.original_collection.to_string(),
+        msg,
+        funds: vec![],
+    }))
+}
+
+/// stop counting a mirrored token sent to another chain once it is back on this chain or burnt
+/// and drop its proposed recovery, nothing is done for a token which was not sent
+fn settle_bridged_token(
+    storage: &mut dyn Storage,
+    now: Timestamp,
+    wrap_data_index: u64,
+    wrap_data: &mut WrapData,
+    mirrored_token_id: &str,
+    state: &BridgeState,
+) -> StdResult<()> {
+    let (channel, window_start) = match state {
+        BridgeState::InFlight {
+            channel,
+            window_start,
+            ..
+        }
+        | BridgeState::Bridged {
+            channel,
+            window_start,
+            ..
+        } => (channel, window_start),
+        BridgeState::Wrapped | BridgeState::Returned => return Ok(()),
+    };
+    wrap_data.bridged_out = wrap_data.bridged_out.saturating_sub(1);
+
+    // the token is no longer stranded, a later bridge needs a new proposal
+    RECOVERIES.remove(storage, (wrap_data_index, mirrored_token_id));
+
+    // the token only frees the limit of the window it was counted in,
+    // the usage of a later window does not include it
+    if let (Some(window_start), Some(rate_limit)) =
+        (window_start, RATE_LIMITS.may_load(storage, channel)?)
+    {
+        let mut rate_limit = rate_limit.at(now);
+        if rate_limit.window_start == *window_start {
+            rate_limit.used = rate_limit.used.saturating_sub(1);
+            RATE_LIMITS.save(storage, channel, &rate_limit)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// the messages updating the metadata of mirrored tokens from their original tokens,
+/// the mirrored collection must be a mirrored-cw721 contract
+fn update_token_metadata_msgs(
+    deps: Deps,
+    env: &Env,
+    wrap_data_index: u64,
+    wrap_data: &WrapData,
+    token_ids: &[String],
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let revealed = is_revealed(&wrap_data.mirrored_data, wrap_data.revealed, env.block.time);
+    let mut msgs = vec![];
+
+    for token_id in token_ids.iter() {
+        let original_token_id = original_token_id(deps.storage, wrap_data_index, token_id)?;
+
+        // query info of the original token
+        let token_info: Cw721NftInfoResponse<Extension> = deps
+            .querier
+            .query(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: wrap_data.original_collection.to_string(),
+                msg: to_json_binary(&Cw721QueryMsg::NftInfo {
+                    token_id: original_token_id.clone(),
+                })?,
+            }))
+            .map_err(|_| ContractError::CustomError {
+                val: format!("Cannot query token info of {}", original_token_id),
+            })?;
+
+        let token_uri = mirrored_token_uri(
+            &wrap_data.mirrored_data,
+            revealed,
+            token_id,
+            &original_token_id,
+            token_info.token_uri,
+        )?;
+
+        let update_msg: Cw721BaseExecuteMsg<Extension, MirrorExecuteExt> =
+            Cw721BaseExecuteMsg::Extension {
+                msg: MirrorExecuteExt::UpdateTokenMetadata {
+                    token_id: token_id.to_string(),
+                    token_uri,
+                    extension: token_info.extension,
+                },
+            };
+        msgs.push(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: wrap_data.mirrored_collection.to_string(),
+            msg: to_json_binary(&update_msg)?,
+            funds: vec![],
+        }));
+    }
+
+    Ok(msgs)
+}
+
+/// the mirrored token id of an original token, derived by the strategy of the mirror
+/// when the token is wrapped for the first time
+fn mirrored_token_id(
+    storage: &mut dyn Storage,
+    wrap_data_index: u64,
+    wrap_data: &mut WrapData,
+    original_token_id: &str,
+) -> Result<String, ContractError> {
+    if let Some(mirrored_token_id) =
+        MIRRORED_TOKEN_IDS.may_load(storage, (wrap_data_index, original_token_id))?
+    {
+        return Ok(mirrored_token_id);
+    }
+
+    let mirrored_token_id = derive_mirrored_token_id(wrap_data, original_token_id);
+
+    // the mirrored token id must not be used by another original token
+    if ORIGINAL_TOKEN_IDS.has(storage, (wrap_data_index, &mirrored_token_id)) {
+        return Err(ContractError::TokenIdConflict {
+            val: mirrored_token_id,
+        });
+    }
+
+    MIRRORED_TOKEN_IDS.save(
+        storage,
+        (wrap_data_index, original_token_id),
+        &mirrored_token_id,
+    )?;
+    ORIGINAL_TOKEN_IDS.save(
+        storage,
+        (wrap_data_index, &mirrored_token_id),
+        &original_token_id.to_string(),
+    )?;
+
+    Ok(mirrored_token_id)
+}
+
+/// derive a new mirrored token id from an original token id by the strategy of the mirror
+fn derive_mirrored_token_id(wrap_data: &mut WrapData, original_token_id: &str) -> String {
+    match wrap_data
+        .mirrored_data
+        .token_id_strategy
+        .clone()
+        .unwrap_or_default()
+    {
+        TokenIdStrategy::Identity => original_token_id.to_string(),
+        TokenIdStrategy::Prefixed { prefix } => format!("{}{}", prefix, original_token_id),
+        TokenIdStrategy::Sequential => {
+            wrap_data.token_id_sequence += 1;
+            wrap_data.token_id_sequence.to_string()
+        }
+        TokenIdStrategy::Hashed => sha256_hex(original_token_id),
+    }
+}
+
+/// the original token id of a mirrored token
+fn original_token_id(
+    storage: &dyn Storage,
+    wrap_data_index: u64,
+    mirrored_token_id: &str,
+) -> StdResult<String> {
+    // the tokens wrapped before v0.2.0 have no mapping and keep the original token id
+    Ok(ORIGINAL_TOKEN_IDS
+        .may_load(storage, (wrap_data_index, mirrored_token_id))?
+        .unwrap_or_else(|| mirrored_token_id.to_string()))
+}
+
+/// remove the escrow record of an original token released from the mirror
+fn release_escrow(
+    storage: &mut dyn Storage,
+    wrap_data_index: u64,
+    wrap_data: &mut WrapData,
+    token_id: &str,
+) -> Result<(), ContractError> {
+    let key = (&wrap_data.original_collection, token_id);
+    // the tokens wrapped before v0.2.0 have no escrow record
+    if let Some(escrow) = ESCROWS.may_load(storage, key)? {
+        if escrow.wrap_data_index != wrap_data_index {
+            return Err(ContractError::WrappedInOtherMirror {
+                val: token_id.to_string(),
+            });
+        }
+        if escrow.status == EscrowStatus::Recovered {
+            return Err(ContractError::AlreadyRecovered {
+                val: token_id.to_string(),
+            });
+        }
+        ESCROWS.remove(storage, key);
+        wrap_data.escrowed = wrap_data.escrowed.saturating_sub(1);
+    }
+
+    Ok(())
+}
+
+pub fn query_controller(deps: Deps) -> StdResult<Addr> {
+    Ok(CONFIG.load(deps.storage)?.controller)
+}
+
+/// the provenance of a collection received through the configured ics721 contract,
+/// none if it is not an ics721 voucher
+fn query_provenance_of_collection(
+    deps: Deps,
+    collection_address: &str,
+) -> Result<Option<Provenance>, ContractError> {
+    let ics721 = match CONFIG.load(deps.storage)?.ics721 {
+        Some(ics721) => ics721,
+        None => return Ok(None),
+    };
+
+    let class_id: StdResult<Option<String>> =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: ics721.to_string(),
+            msg: to_json_binary(&Ics721QueryMsg::ClassId {
+                contract: collection_address.to_string(),
+            })?,
+        }));
+    let class_id = match class_id {
+        Ok(Some(class_id)) => class_id,
+        _ => return Ok(None),
+    };
+
+    // the class id of a native collection has no trace
+    let (channel_id, base_class_id, hops) = match parse_class_trace(&class_id) {
+        Some(trace) => trace,
+        None => return Ok(None),
+    };
+    let previous_chain = BRIDGE_CHANNELS
+        .may_load(deps.storage, &channel_id)?
+        .map(|channel| channel.chain_id);
+
+    Ok(Some(Provenance {
+        class_id,
+        base_class_id,
+        channel_id,
+        previous_chain,
+        hops,
+    }))
+}
+
+/// the chain a collection was created on, this chain for a native collection,
+/// the previous chain of a voucher collection received in a single hop, none if unknown
+fn original_chain(provenance: Option<&Provenance>, env: &Env) -> Option<String> {
+    match provenance {
+        None => Some(env.block.chain_id.clone()),
+        Some(provenance) if provenance.hops == 1 => provenance.previous_chain.clone(),
+        // the counterparty chains of the previous hops are not known by the wrapper
+        Some(_) => None,
+    }
+}
+
+/// check the mirrored token ids the original tokens are wrapped into against the ics721
+/// compatibility policy, all token ids are compatible if there is no policy
+pub fn query_check_token_ids(
+    deps: Deps,
+    collection_address: String,
+    mirror_index: Option<u32>,
+    token_ids: Vec<String>,
+) -> StdResult<Vec<TokenIdCheckResponse>> {
+    let policy = CONFIG.load(deps.storage)?.ics721_policy;
+
+    let mirror_index = mirror_index.unwrap_or(0);
+    let wrap_data_index = *ORIGINAL_COLLECTIONS
+        .load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .get(mirror_index as usize)
+        .ok_or_else(|| StdError::not_found("wrap data"))?;
+    // the sequence of the mirror is only advanced in memory
+    let mut wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+
+    token_ids
+        .into_iter()
+        .map(|token_id| {
+            let mirrored_token_id =
+                match MIRRORED_TOKEN_IDS.may_load(deps.storage, (wrap_data_index, &token_id))? {
+                    Some(mirrored_token_id) => mirrored_token_id,
+                    None => derive_mirrored_token_id(&mut wrap_data, &token_id),
+                };
+            let error = policy
+                .as_ref()
+                .and_then(|policy| validate_token_id(policy, &mirrored_token_id).err())
+                .map(|err| err.to_string());
+            Ok(TokenIdCheckResponse {
+                token_id,
+                mirrored_token_id,
+                error,
+            })
+        })
+        .collect()
+}
+
+/// the ics721 class data of a mirrored collection
+pub fn query_class_data(deps: Deps, env: Env, collection_address: String) -> StdResult<ClassData> {
+    let wrap_data_index =
+        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?;
+    let wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+
+    // the name and symbol of the mirrored collection
+    let contract_info: Cw721ContractInfoResponse =
+        deps.querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+            contract_addr: wrap_data.mirrored_collection.to_string(),
+            msg: to_json_binary(&Cw721QueryMsg::ContractInfo {})?,
+        }))?;
+
+    let original_chain = original_chain(wrap_data.provenance.as_ref(), &env);
+
+    Ok(ClassData {
+        name: contract_info.name,
+        symbol: contract_info.symbol,
+        original_collection: wrap_data.original_collection.to_string(),
+        original_chain,
+        base_uri: wrap_data.mirrored_data.base_uri,
+        royalty_info: wrap_data.mirrored_data.royalty_info,
+        wrapper: env.contract.address.to_string(),
+    })
+}
+
+/// the provenance of an original or a mirrored collection
+pub fn query_provenance(deps: Deps, collection_address: String) -> StdResult<Option<Provenance>> {
+    let collection_address = deps.api.addr_validate(&collection_address)?;
+
+    let wrap_data_index =
+        match MIRRORED_COLLECTIONS.may_load(deps.storage, collection_address.clone())? {
+            Some(wrap_data_index) => wrap_data_index,
+            // all mirrors of an original collection share its provenance
+            None => ORIGINAL_COLLECTIONS
+                .load(deps.storage, collection_address)?
+                .first()
+                .copied()
+                .ok_or_else(|| StdError::not_found("wrap data"))?,
+        };
+
+    Ok(WRAP_DATA.load(deps.storage, wrap_data_index)?.provenance)
+}
+
+pub fn query_recovery(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<Option<Recovery>> {
+    let wrap_data_index = MIRRORED_COLLECTIONS
+        .may_load(deps.storage, deps.api.addr_validate(&collection_address)?)?
+        .ok_or_else(|| StdError::generic_err(ContractError::CollectionNotAllowed {}.to_string()))?;
+
+    RECOVERIES.may_load(deps.storage, (wrap_data_index, &token_id))
+}
+
+pub fn query_bridge_state(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<Option<BridgeState>> {
+    let wrap_data_index =
+        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?;
+
+    BRIDGE_STATES.may_load(deps.storage, (wrap_data_index, &token_id))
+}
+
+pub fn query_tokens_in_bridge_status(
+    deps: Deps,
+    collection_address: String,
+    status: BridgeStatus,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<String>> {
+    let wrap_data_index =
+        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    // the tokens in the status are ordered by wrap data index, then mirrored token id
+    let start = match &start_after {
+        Some(start_after) => Bound::exclusive((wrap_data_index, start_after.as_str())),
+        None => Bound::inclusive((wrap_data_index, "")),
+    };
+    let end = Bound::exclusive((wrap_data_index + 1, ""));
+
+    BRIDGE_STATES
+        .idx
+        .status
+        .prefix(status.as_str().to_string())
+        .keys(deps.storage, Some(start), Some(end), Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(_, token_id)| token_id))
+        .collect()
+}
+
+pub fn query_relayers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<Addr>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|start_after| deps.api.addr_validate(&start_after))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    RELAYERS
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect()
+}
+
+pub fn query_mirrored_token_id(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<String> {
+    let wrap_data_index =
+        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?;
+
+    // the tokens wrapped before v0.2.0 have no mapping and keep the original token id
+    Ok(MIRRORED_TOKEN_IDS
+        .may_load(deps.storage, (wrap_data_index, &token_id))?
+        .unwrap_or(token_id))
+}
+
+pub fn query_original_token_id(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<String> {
+    let wrap_data_index =
+        MIRRORED_COLLECTIONS.load(deps.storage, deps.api.addr_validate(&collection_address)?)?;
+
+    original_token_id(deps.storage, wrap_data_index, &token_id)
+}
+
+pub fn query_token_link(
+    deps: Deps,
+    collection_address: String,
+    token_id: String,
+) -> StdResult<TokenLinkResponse> {
+    let collection_address = deps.api.addr_validate(&collection_address)?;
+
+    // the token is an original token
+    if ORIGINAL_COLLECTIONS.has(deps.storage, collection_address.clone()) {
+        let escrow = ESCROWS.may_load(deps.storage, (&collection_address, &token_id))?;
+        let (mirrored_collection, mirrored_token_id) = match &escrow {
+            Some(escrow) => {
+                let wrap_data = WRAP_DATA.load(deps.storage, escrow.wrap_data_index)?;
+                let mirrored_token_id = MIRRORED_TOKEN_IDS
+                    .may_load(deps.storage, (escrow.wrap_data_index, &token_id))?
+                    .unwrap_or_else(|| token_id.clone());
+                (Some(wrap_data.mirrored_collection), Some(mirrored_token_id))
+            }
+            None => (None, None),
+        };
+
+        return Ok(TokenLinkResponse {
+            original_collection: collection_address,
+            original_token_id: token_id,
+            mirrored_collection,
+            mirrored_token_id,
+            wrapped: escrow
+                .as_ref()
+                .map_or(false, |escrow| escrow.status == EscrowStatus::Escrowed),
+            escrow,
+        });
+    }
+
+    // the token is a mirrored token
+    let wrap_data_index = MIRRORED_COLLECTIONS.load(deps.storage, collection_address.clone())?;
+    let wrap_data = WRAP_DATA.load(deps.storage, wrap_data_index)?;
+    let original_token_id = original_token_id(deps.storage, wrap_data_index, &token_id)?;
+    let escrow = ESCROWS
+        .may_load(
+            deps.storage,
+            (&wrap_data.original_collection, &original_token_id),
+        )?
+        .filter(|escrow| escrow.wrap_data_index == wrap_data_index);
+
+    // the tokens wrapped before v0.2.0 have no escrow record,
+    // they are wrapped as long as the mirrored token exists
+    let wrapped = escrow
+        .as_ref()
+        .map_or(false, |escrow| escrow.status == EscrowStatus::Escrowed)
+        || deps
+            .querier
+            .query::<OwnerOfResponse>(&QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: collection_address.to_string(),
+                msg: to_json_binary(&Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.clone(),
+                    include_expired: None,
+                })?,
+            }))
+            .is_ok();
+
+    Ok(TokenLinkResponse {
+        original_collection: wrap_data.original_collection,
+        original_token_id,
+        mirrored_collection: Some(collection_address),
+        mirrored_token_id: Some(token_id),
+        wrapped,
+        escrow,
+    })
 }
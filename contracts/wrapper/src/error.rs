@@ -21,11 +21,112 @@ pub enum ContractError {
     #[error("Collection deactivated")]
     CollectionDeactivated {},
 
+    #[error("Mirror {val:?} not found")]
+    MirrorNotFound { val: u32 },
+
+    #[error("Token_id {val:?} already wrapped")]
+    AlreadyWrapped { val: String },
+
+    #[error("Token_id {val:?} wrapped into another mirror")]
+    WrappedInOtherMirror { val: String },
+
+    #[error("Mirrored token_id {val:?} already mapped to another original token")]
+    TokenIdConflict { val: String },
+
+    #[error("Token_id {val:?} refreshed too recently")]
+    RefreshTooSoon { val: String },
+
+    #[error("Contract paused")]
+    Paused {},
+
     #[error("Token_id {val:?} not owned by sender")]
     NotOwnedBySender { val: String },
 
+    #[error(
+        "Wrapper not approved on token_id {val:?}, approve it or send the token to the wrapper"
+    )]
+    WrapperNotApproved { val: String },
+
+    #[error("Recipient cannot be set with a post action")]
+    RecipientWithPostAction {},
+
+    #[error("Ics721 contract not configured")]
+    Ics721NotConfigured {},
+
+    #[error("Channel {val:?} not registered")]
+    UnknownChannel { val: String },
+
+    #[error("Token_id {val:?} is not stranded on another chain")]
+    NotStranded { val: String },
+
+    #[error("Recovery of token_id {val:?} not found")]
+    RecoveryNotFound { val: String },
+
+    #[error("Recovery of token_id {val:?} still timelocked")]
+    RecoveryTimelocked { val: String },
+
+    #[error("Token_id {val:?} already recovered")]
+    AlreadyRecovered { val: String },
+
+    #[error("Recovery timelock {val:?} shorter than {min:?} seconds")]
+    RecoveryTimelockTooShort { val: u64, min: u64 },
+
+    #[error("Rate limit of channel {val:?} exceeded")]
+    RateLimitExceeded { val: String },
+
+    #[error("Invalid rate limit window: {val:?}")]
+    InvalidRateLimitWindow { val: u64 },
+
+    #[error("Invalid receiver {val:?}")]
+    InvalidReceiver { val: String },
+
+    #[error("Receiver {val:?} does not have the prefix {expected:?} of the counterparty chain")]
+    ReceiverPrefixMismatch { val: String, expected: String },
+
+    #[error("Empty token_id")]
+    EmptyTokenId {},
+
+    #[error("Token_id {val:?} too long for ics721")]
+    TokenIdTooLong { val: String },
+
+    #[error("Token_id {val:?} contains characters not supported by ics721")]
+    InvalidTokenIdCharacters { val: String },
+
+    #[error("Collection name {val:?} not supported by ics721")]
+    InvalidCollectionName { val: String },
+
+    #[error("Collection symbol {val:?} not supported by ics721")]
+    InvalidCollectionSymbol { val: String },
+
+    #[error("Invalid token uri template: {val:?}")]
+    InvalidTokenUriTemplate { val: String },
+
+    #[error("Invalid royalty percentage: {val:?}")]
+    InvalidRoyaltyPercentage { val: u64 },
+
+    #[error("Invalid instantiate message template: {val:?}")]
+    InvalidInstantiateTemplate { val: String },
+
+    #[error("Cannot migrate from contract {previous_contract:?}")]
+    CannotMigrateContract { previous_contract: String },
+
+    #[error("Cannot migrate from version {previous_version:?} to older version {new_version:?}")]
+    CannotMigrateVersion {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+
     #[error("Custom Error val: {val:?}")]
     CustomError { val: String },
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
 }
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
@@ -0,0 +1,176 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, IbcTimeout};
+
+use crate::error::ContractError;
+use crate::state::{ChannelInfo, Ics721Policy};
+
+/// the message attached to a token sent to the ics721 contract to bridge it
+#[cw_serde]
+pub struct IbcOutgoingMsg {
+    /// the address receiving the token on the counterparty chain
+    pub receiver: String,
+    /// the local channel the token is sent through
+    pub channel_id: String,
+    pub timeout: IbcTimeout,
+    pub memo: Option<String>,
+}
+
+/// the memo of a transfer asking the ics721 contract for callbacks
+#[cw_serde]
+pub struct Ics721Memo {
+    pub callbacks: Option<Ics721Callbacks>,
+}
+
+#[cw_serde]
+pub struct Ics721Callbacks {
+    /// the data passed back to the ack callback, no ack callback is made without it
+    pub ack_callback_data: Option<Binary>,
+    /// the contract receiving the ack callback, default to the sender of the tokens
+    pub ack_callback_addr: Option<String>,
+    pub receive_callback_data: Option<Binary>,
+    pub receive_callback_addr: Option<String>,
+}
+
+/// the message the ics721 contract executes once a transfer is acknowledged or timed out
+#[cw_serde]
+pub struct Ics721AckCallbackMsg {
+    pub status: Ics721Status,
+    /// the local collection of the transferred tokens
+    pub nft_contract: String,
+    pub original_packet: NonFungibleTokenPacketData,
+    /// the ack callback data of the memo
+    pub msg: Binary,
+}
+
+#[cw_serde]
+pub enum Ics721Status {
+    Success,
+    Failed(String),
+}
+
+/// the packet data of an ics721 transfer
+#[cw_serde]
+#[serde(rename_all = "camelCase")]
+pub struct NonFungibleTokenPacketData {
+    pub class_id: String,
+    pub class_uri: Option<String>,
+    pub class_data: Option<Binary>,
+    pub token_ids: Vec<String>,
+    pub token_uris: Option<Vec<String>>,
+    pub token_data: Option<Vec<Binary>>,
+    pub sender: String,
+    pub receiver: String,
+    pub memo: Option<String>,
+}
+
+/// the queries of the ics721 contract used by the wrapper
+#[cw_serde]
+pub enum Ics721QueryMsg {
+    /// the class id of a local collection, none if it never went through the ics721 contract,
+    /// the class id of a voucher collection is prefixed by the ports and channels it came through
+    ClassId { contract: String },
+}
+
+/// the local channel, the base class id and the number of hops of the class id
+/// of a voucher collection, none if the class id has no trace
+pub fn parse_class_trace(class_id: &str) -> Option<(String, String, u32)> {
+    let mut segments = class_id.splitn(3, '/');
+    let (_port, channel_id, mut base_class_id) =
+        (segments.next()?, segments.next()?, segments.next()?);
+    if !channel_id.starts_with("channel-") {
+        return None;
+    }
+
+    // skip the next hops of a multi-hop trace
+    let mut hops = 1;
+    while let Some((_port, rest)) = base_class_id.split_once('/') {
+        match rest.split_once('/') {
+            Some((channel, rest)) if channel.starts_with("channel-") => {
+                base_class_id = rest;
+                hops += 1;
+            }
+            _ => break,
+        }
+    }
+
+    Some((channel_id.to_string(), base_class_id.to_string(), hops))
+}
+
+/// check a mirrored token id against the ics721 compatibility policy
+pub fn validate_token_id(policy: &Ics721Policy, token_id: &str) -> Result<(), ContractError> {
+    if token_id.is_empty() {
+        return Err(ContractError::EmptyTokenId {});
+    }
+    if token_id.len() > policy.max_token_id_length as usize {
+        return Err(ContractError::TokenIdTooLong {
+            val: token_id.to_string(),
+        });
+    }
+    if !token_id
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || policy.token_id_extra_chars.contains(c))
+    {
+        return Err(ContractError::InvalidTokenIdCharacters {
+            val: token_id.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// check the name and symbol of a mirrored collection against the ics721 compatibility policy
+pub fn validate_collection_info(
+    policy: &Ics721Policy,
+    name: &str,
+    symbol: &str,
+) -> Result<(), ContractError> {
+    if name.is_empty() || name.len() > policy.max_name_length as usize {
+        return Err(ContractError::InvalidCollectionName {
+            val: name.to_string(),
+        });
+    }
+    if symbol.is_empty()
+        || symbol.len() > policy.max_symbol_length as usize
+        || !symbol.chars().all(|c| c.is_ascii_alphanumeric())
+    {
+        return Err(ContractError::InvalidCollectionSymbol {
+            val: symbol.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// check the receiver of a transfer has the bech32 prefix of the counterparty chain,
+/// a receiver with the local prefix is converted if allowed by the channel
+pub fn validate_receiver(
+    channel: &ChannelInfo,
+    receiver: &str,
+    local_address: &Addr,
+) -> Result<String, ContractError> {
+    let prefix = match &channel.bech32_prefix {
+        Some(prefix) => prefix,
+        None => return Ok(receiver.to_string()),
+    };
+
+    let (hrp, data, variant) =
+        bech32::decode(receiver).map_err(|_| ContractError::InvalidReceiver {
+            val: receiver.to_string(),
+        })?;
+    if hrp == *prefix {
+        return Ok(receiver.to_string());
+    }
+
+    // the local prefix is the one of the wrapper address
+    let local_prefix = bech32::decode(local_address.as_str()).map(|(hrp, _, _)| hrp);
+    if channel.convert_local_receiver && local_prefix.map_or(false, |local| local == hrp) {
+        return bech32::encode(prefix, data, variant).map_err(|_| ContractError::InvalidReceiver {
+            val: receiver.to_string(),
+        });
+    }
+
+    Err(ContractError::ReceiverPrefixMismatch {
+        val: receiver.to_string(),
+        expected: prefix.to_string(),
+    })
+}
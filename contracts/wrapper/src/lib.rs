@@ -1,7 +1,10 @@
 pub mod contract;
-mod error;
+pub mod error;
+pub mod ics721;
+mod migrations;
 pub mod msg;
 pub mod state;
+pub mod token_uri;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,99 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, DepsMut, Order, StdResult};
+use cw_storage_plus::{Item, Map};
+
+use crate::contract::{
+    DEFAULT_BRIDGE_TIMEOUT, DEFAULT_RECOVERY_TIMELOCK, DEFAULT_REFRESH_INTERVAL,
+};
+use crate::error::ContractError;
+use crate::state::{
+    Config, MirroredData, TokenIdStrategy, WrapData, CONFIG, ORIGINAL_COLLECTIONS, WRAP_DATA,
+};
+
+/// the mirrored data of v0.1.x
+#[cw_serde]
+struct MirroredDataV0_1 {
+    pub collection_name: Option<String>,
+    pub collection_symbol: Option<String>,
+    pub base_uri: Option<String>,
+}
+
+/// the wrap data of v0.1.x
+#[cw_serde]
+struct WrapDataV0_1 {
+    pub original_collection: Addr,
+    pub mirrored_collection: Addr,
+    pub mirrored_data: MirroredDataV0_1,
+    pub active: bool,
+}
+
+/// fold the separated `controller`, `cw721-code-id` and `total-wrapped` items of v0.1.x
+/// into the single `config` item, allow many mirrors per original collection
+/// and backfill the new fields of wrap data
+pub fn v0_2_0(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy_controller: Item<Addr> = Item::new("controller");
+    let legacy_cw721_code_id: Item<u64> = Item::new("cw721-code-id");
+    let legacy_total_wrapped: Item<u64> = Item::new("total-wrapped");
+
+    let config = Config {
+        controller: legacy_controller.load(deps.storage)?,
+        cw721_code_id: legacy_cw721_code_id.load(deps.storage)?,
+        total_wrapped: legacy_total_wrapped.load(deps.storage)?,
+        paused: false,
+        wrapper_as_mirror_admin: false,
+        refresh_interval: DEFAULT_REFRESH_INTERVAL,
+        ics721: None,
+        bridge_timeout: DEFAULT_BRIDGE_TIMEOUT,
+        ics721_policy: None,
+        recovery_timelock: DEFAULT_RECOVERY_TIMELOCK,
+    };
+    CONFIG.save(deps.storage, &config)?;
+
+    // all mirrored collections of v0.1.x were instantiated by the current code id
+    // with the controller as admin
+    let legacy_wrap_data: Map<u64, WrapDataV0_1> = Map::new("wrap-data");
+    let legacy_entries = legacy_wrap_data
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (index, legacy) in legacy_entries {
+        let wrap_data = WrapData {
+            original_collection: legacy.original_collection,
+            mirrored_collection: legacy.mirrored_collection,
+            mirrored_data: MirroredData {
+                collection_name: legacy.mirrored_data.collection_name,
+                collection_symbol: legacy.mirrored_data.collection_symbol,
+                base_uri: legacy.mirrored_data.base_uri,
+                // the tokens of v0.1.x were minted with the original token id
+                token_id_strategy: Some(TokenIdStrategy::Identity),
+                token_uri_template: None,
+                reveal: None,
+                royalty_info: None,
+            },
+            active: legacy.active,
+            code_id: config.cw721_code_id,
+            admin: Some(config.controller.clone()),
+            // the tokens wrapped in v0.1.x have no escrow record
+            escrowed: 0,
+            token_id_sequence: 0,
+            revealed: false,
+            bridged_out: 0,
+            provenance: None,
+        };
+        WRAP_DATA.save(deps.storage, index, &wrap_data)?;
+    }
+
+    // each original collection had exactly one mirror in v0.1.x
+    let legacy_original_collections: Map<Addr, u64> = Map::new("original-collections");
+    let legacy_entries = legacy_original_collections
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (original_collection, index) in legacy_entries {
+        ORIGINAL_COLLECTIONS.save(deps.storage, original_collection, &vec![index])?;
+    }
+
+    legacy_controller.remove(deps.storage);
+    legacy_cw721_code_id.remove(deps.storage);
+    legacy_total_wrapped.remove(deps.storage);
+
+    Ok(())
+}
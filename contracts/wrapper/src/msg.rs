@@ -1,6 +1,15 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::Binary;
 
-use crate::state::{MirroredData, WrapData};
+use cosmwasm_std::Addr;
+use cw721::Cw721ReceiveMsg;
+use mirrored_cw721::msg::ClassData;
+
+use crate::ics721::Ics721AckCallbackMsg;
+use crate::state::{
+    BridgeState, BridgeStatus, ChannelInfo, Escrow, Ics721Policy, MirroredData, Provenance,
+    RateLimit, Recovery, WrapData,
+};
 
 /// Message type for `instantiate` entry_point
 /// Maybe we don't need a new cw20 contract, just use the cw20-base contract
@@ -8,22 +17,226 @@ use crate::state::{MirroredData, WrapData};
 pub struct InstantiateMsg {
     pub controller: String,
     pub cw721_code_id: u64,
+    pub wrapper_as_mirror_admin: Option<bool>,
+    pub refresh_interval: Option<u64>,
+    pub ics721: Option<String>,
+    pub bridge_timeout: Option<u64>,
+    pub ics721_policy: Option<Ics721Policy>,
+    pub recovery_timelock: Option<u64>,
 }
 
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
+
 /// Message type for `execute` entry_point
 #[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Wrap {
         collection_address: String,
         token_ids: Vec<String>,
+        /// the position of the target mirror of the original collection, default to 0
+        mirror_index: Option<u32>,
+        /// the receiver of the mirrored tokens, default to the owner of the original tokens
+        recipient: Option<String>,
+        /// the owner of the original tokens, only relayers can wrap for another owner
+        owner: Option<String>,
+        /// send the mirrored tokens to a contract instead of minting them to the recipient
+        then: Option<PostAction>,
     },
     Unwrap {
         collection_address: String,
         token_ids: Vec<String>,
+        /// the receiver of the original tokens, default to the owner of the mirrored tokens
+        recipient: Option<String>,
+        /// the owner of the mirrored tokens, only relayers can unwrap for another owner
+        owner: Option<String>,
+        /// send the original tokens to a contract instead of transferring them to the recipient
+        then: Option<PostAction>,
+    },
+    /// unwrap the mirrored tokens and bridge the original tokens through the ics721 contract
+    UnwrapAndBridge {
+        collection_address: String,
+        token_ids: Vec<String>,
+        /// a channel registered by the controller
+        channel_id: String,
+        /// the address receiving the original tokens on the counterparty chain
+        receiver: String,
+        /// the seconds before the transfer times out, default to the bridge timeout of the config
+        timeout_seconds: Option<u64>,
+    },
+    /// unwrap or bridge a mirrored token sent by `SendNft` with a `ReceiveMsg`
+    ReceiveNft(Cw721ReceiveMsg),
+    /// the callback of the ics721 contract for the mirrored tokens bridged by the wrapper
+    Ics721AckCallback(Ics721AckCallbackMsg),
+    /// mark the bridged mirrored tokens no longer held by the ics721 contract as returned
+    SyncBridgeStates {
+        collection_address: String,
+        token_ids: Vec<String>,
+    },
+    /// register the counterparty chain of a channel the mirrored tokens can be bridged through
+    SetBridgeChannel {
+        channel_id: String,
+        chain_id: String,
+        /// the bech32 prefix the receivers must have on the counterparty chain
+        bech32_prefix: Option<String>,
+        /// convert the receivers with the local prefix, default to false
+        convert_local_receiver: Option<bool>,
+    },
+    RemoveBridgeChannel {
+        channel_id: String,
+    },
+    /// limit the mirrored tokens bridged out through a channel in a fixed window
+    SetRateLimit {
+        channel_id: String,
+        max_tokens: u64,
+        window_seconds: u64,
+    },
+    RemoveRateLimit {
+        channel_id: String,
+    },
+    /// propose to release the original token of a mirrored token stranded on another chain,
+    /// the recovery can be executed once the recovery timelock is over
+    ProposeRecovery {
+        collection_address: String,
+        token_id: String,
+        recipient: String,
+    },
+    /// burn the stranded mirrored token and release its original token to the recipient
+    ExecuteRecovery {
+        collection_address: String,
+        token_id: String,
+    },
+    CancelRecovery {
+        collection_address: String,
+        token_id: String,
+    },
+    /// set the ics721 compatibility policy, none to stop checking collections and tokens
+    SetIcs721Policy {
+        policy: Option<Ics721Policy>,
     },
     RegisterCollection {
         original_collection: String,
         new_collection: MirroredData,
+        instantiate_options: Option<InstantiateOptions>,
+    },
+    UpdateController {
+        controller: String,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    /// allow a contract to wrap and unwrap for the owners approving it
+    AddRelayer {
+        relayer: String,
+    },
+    RemoveRelayer {
+        relayer: String,
+    },
+    SetCollectionStatus {
+        collection_address: String,
+        active: bool,
+    },
+    /// update the name and symbol of a mirrored-cw721 collection
+    UpdateCollectionInfo {
+        collection_address: String,
+        name: Option<String>,
+        symbol: Option<String>,
+    },
+    UpdateConfig {
+        cw721_code_id: Option<u64>,
+        wrapper_as_mirror_admin: Option<bool>,
+        refresh_interval: Option<u64>,
+        ics721: Option<String>,
+        bridge_timeout: Option<u64>,
+        recovery_timelock: Option<u64>,
+    },
+    /// migrate the mirrored collections administrated by the wrapper to a new code id
+    MigrateMirrors {
+        code_id: u64,
+        msg: Binary,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// reveal the mirrored collection and update the token uri of the given mirrored tokens
+    Reveal {
+        collection_address: String,
+        token_ids: Vec<String>,
+    },
+    /// push the current metadata of the original tokens to the mirrored tokens
+    RefreshMetadata {
+        collection_address: String,
+        token_ids: Vec<String>,
+    },
+}
+
+/// the message attached to a mirrored token sent to the wrapper
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// burn the mirrored token and release the original token to the sender
+    Unwrap {},
+    /// bridge the mirrored token through the ics721 contract
+    Bridge {
+        channel_id: String,
+        /// the address receiving the mirrored token on the counterparty chain
+        receiver: String,
+        /// the seconds before the transfer times out, default to the bridge timeout of the config
+        timeout_seconds: Option<u64>,
+    },
+}
+
+/// a contract receiving the tokens by `SendNft` with the given message
+#[cw_serde]
+pub struct PostAction {
+    pub contract: String,
+    pub msg: Binary,
+}
+
+/// the message of the `SendNft` delivering the tokens to the contract of a post action,
+/// the sender of the `Cw721ReceiveMsg` is always the wrapper, so the contract must trust
+/// the beneficiary only when the sender is the wrapper and read it from this message
+#[cw_serde]
+pub struct PostActionMsg {
+    /// the owner of the tokens before they were wrapped or unwrapped
+    pub beneficiary: String,
+    /// the message of the post action
+    pub msg: Binary,
+}
+
+/// the options used to instantiate the mirrored collection
+#[cw_serde]
+pub struct InstantiateOptions {
+    /// the wasm admin, default to the admin decided by the config
+    pub admin: Option<String>,
+    /// the label, default to "Instantiate mirror collection for <original_collection>"
+    pub label: Option<String>,
+    /// the code id of a cw721 variant, default to the code id in the config
+    pub code_id: Option<u64>,
+    /// a custom instantiate message, the placeholders `{name}`, `{symbol}` and `{minter}`
+    /// are replaced by the json strings of the mirrored name, symbol and the wrapper address,
+    /// `{minter}` is required
+    pub msg_template: Option<String>,
+}
+
+/// Message type for `sudo` entry_point, used by the chain governance
+#[cw_serde]
+pub enum SudoMsg {
+    UpdateController {
+        controller: String,
+    },
+    SetPaused {
+        paused: bool,
+    },
+    SetCollectionStatus {
+        collection_address: String,
+        active: bool,
+    },
+    /// burn the mirrored tokens and release their original tokens to the recipient
+    ForceUnwrap {
+        collection_address: String,
+        token_ids: Vec<String>,
+        recipient: String,
     },
 }
 
@@ -33,12 +246,97 @@ pub enum ExecuteMsg {
 pub enum QueryMsg {
     #[returns(String)]
     Controller {},
-    #[returns(u64)]
+    #[returns(Vec<u64>)]
     OriginalCollectionInfo { collection_address: String },
     #[returns(u64)]
     MirroredCollectionInfo { collection_address: String },
     #[returns(WrapData)]
     WrapData { index: u64 },
+    /// the mirrored token id of an original token in the mirrored collection
+    #[returns(String)]
+    MirroredTokenId {
+        collection_address: String,
+        token_id: String,
+    },
+    /// the original token id of a token in the mirrored collection
+    #[returns(String)]
+    OriginalTokenId {
+        collection_address: String,
+        token_id: String,
+    },
+    /// the link between an original token and its mirrored token,
+    /// the collection address can be either the original or the mirrored collection
+    #[returns(TokenLinkResponse)]
+    TokenLink {
+        collection_address: String,
+        token_id: String,
+    },
+    /// the contracts allowed to wrap and unwrap for other owners
+    #[returns(Vec<Addr>)]
+    Relayers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// check the mirrored token ids of original tokens against the ics721 compatibility policy
+    #[returns(Vec<TokenIdCheckResponse>)]
+    CheckTokenIds {
+        /// the original collection
+        collection_address: String,
+        /// the position of the target mirror of the original collection, default to 0
+        mirror_index: Option<u32>,
+        token_ids: Vec<String>,
+    },
+    /// the ics721 class data of a mirrored collection
+    #[returns(ClassData)]
+    ClassData { collection_address: String },
+    /// the cross-chain origin of an original or a mirrored collection,
+    /// none if the original collection is not an ics721 voucher
+    #[returns(Option<Provenance>)]
+    Provenance { collection_address: String },
+    #[returns(ChannelInfo)]
+    BridgeChannel { channel_id: String },
+    /// the rate limit of a channel and its usage in the current window, none if not limited
+    #[returns(Option<RateLimit>)]
+    RateLimit { channel_id: String },
+    /// the proposed recovery of a mirrored token
+    #[returns(Option<Recovery>)]
+    Recovery {
+        collection_address: String,
+        token_id: String,
+    },
+    /// the bridge state of a mirrored token, none if the token is not wrapped
+    #[returns(Option<BridgeState>)]
+    BridgeState {
+        collection_address: String,
+        token_id: String,
+    },
+    /// the mirrored tokens of a collection in the given bridge status
+    #[returns(Vec<String>)]
+    TokensInBridgeStatus {
+        collection_address: String,
+        status: BridgeStatus,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+#[cw_serde]
+pub struct TokenIdCheckResponse {
+    pub token_id: String,
+    /// the mirrored token id the original token is or would be wrapped into
+    pub mirrored_token_id: String,
+    /// the violation of the ics721 compatibility policy, none if the token id is compatible
+    pub error: Option<String>,
+}
+
+#[cw_serde]
+pub struct TokenLinkResponse {
+    pub original_collection: Addr,
+    pub original_token_id: String,
+    pub mirrored_collection: Option<Addr>,
+    pub mirrored_token_id: Option<String>,
+    pub wrapped: bool,
+    pub escrow: Option<Escrow>,
 }
 
 #[cw_serde]
@@ -1,15 +1,12 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::Addr;
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Empty, Timestamp};
+use cw_storage_plus::{Index, IndexList, IndexedMap, Item, Map, MultiIndex};
 
-// we need a wallet to control the contract
-pub const CONTROLLER: Item<Addr> = Item::new("controller");
+// the configuration of the contract
+pub const CONFIG: Item<Config> = Item::new("config");
 
-// a code_id of cw721 contract
-pub const CW721_CODE_ID: Item<u64> = Item::new("cw721-code-id");
-
-// the original collection mapping
-pub const ORIGINAL_COLLECTIONS: Map<Addr, u64> = Map::new("original-collections");
+// the original collection mapping, an original collection can have many mirrors
+pub const ORIGINAL_COLLECTIONS: Map<Addr, Vec<u64>> = Map::new("original-collections");
 
 // the mirrored collection mapping
 pub const MIRRORED_COLLECTIONS: Map<Addr, u64> = Map::new("mirrored-collections");
@@ -17,37 +14,79 @@ pub const MIRRORED_COLLECTIONS: Map<Addr, u64> = Map::new("mirrored-collections"
 // the wrap data mapping
 pub const WRAP_DATA: Map<u64, WrapData> = Map::new("wrap-data");
 
-// total wrap count
-pub const TOTAL_WRAPPED: Item<u64> = Item::new("total-wrapped");
+// the mirrored token id of an original token, keyed by wrap data index and original token id
+pub const MIRRORED_TOKEN_IDS: Map<(u64, &str), String> = Map::new("mirrored-token-ids");
+
+// the original token id of a mirrored token, keyed by wrap data index and mirrored token id
+pub const ORIGINAL_TOKEN_IDS: Map<(u64, &str), String> = Map::new("original-token-ids");
+
+// the last metadata refresh of a mirrored token, keyed by wrap data index and mirrored token id
+pub const LAST_REFRESHED: Map<(u64, &str), Timestamp> = Map::new("last-refreshed");
+
+// the contracts allowed to wrap and unwrap for other owners
+pub const RELAYERS: Map<&Addr, Empty> = Map::new("relayers");
+
+// the channels the mirrored tokens can be bridged through, keyed by channel id
+pub const BRIDGE_CHANNELS: Map<&str, ChannelInfo> = Map::new("bridge-channels");
+
+// the limits of mirrored tokens bridged out through a channel, keyed by channel id
+pub const RATE_LIMITS: Map<&str, RateLimit> = Map::new("rate-limits");
+
+// the bridge state of mirrored tokens, keyed by wrap data index and mirrored token id
+pub const BRIDGE_STATES: IndexedMap<(u64, &str), BridgeState, BridgeStateIndexes> = IndexedMap::new(
+    "bridge-states",
+    BridgeStateIndexes {
+        status: MultiIndex::new(
+            bridge_status_index,
+            "bridge-states",
+            "bridge-states__status",
+        ),
+    },
+);
 
-// this extension is used to store metadata of cw2981 token
-pub type Extension = Option<Metadata>;
+// the proposed recoveries of stranded mirrored tokens, keyed by wrap data index and mirrored token id
+pub const RECOVERIES: Map<(u64, &str), Recovery> = Map::new("recoveries");
+
+// the escrowed original tokens, keyed by original collection and token id
+pub const ESCROWS: Map<(&Addr, &str), Escrow> = Map::new("escrows");
+
+// the metadata of mirrored tokens, shared with the mirrored-cw721 contract
+pub use mirrored_cw721::state::{Extension, Metadata, RoyaltyInfo, Trait};
+
+/// the configuration of the contract
 #[cw_serde]
-#[derive(Default)]
-pub struct Metadata {
-    pub image: Option<String>,
-    pub image_data: Option<String>,
-    pub external_url: Option<String>,
-    pub description: Option<String>,
-    pub name: Option<String>,
-    pub attributes: Option<Vec<Trait>>,
-    pub background_color: Option<String>,
-    pub animation_url: Option<String>,
-    pub youtube_url: Option<String>,
-    /// This is how much the minter takes as a cut when sold
-    /// royalties are owed on this token if it is Some
-    pub royalty_percentage: Option<u64>,
-    /// The payment address, may be different to or the same
-    /// as the minter addr
-    /// question: how do we validate this?
-    pub royalty_payment_address: Option<String>,
+pub struct Config {
+    // we need a wallet to control the contract
+    pub controller: Addr,
+    // a code_id of cw721 contract
+    pub cw721_code_id: u64,
+    // total wrap count
+    pub total_wrapped: u64,
+    // wrapping and unwrapping are stopped while paused
+    pub paused: bool,
+    // the wrapper itself is the wasm admin of new mirrored collections instead of the controller
+    pub wrapper_as_mirror_admin: bool,
+    // the minimum seconds between two metadata refreshes of a mirrored token
+    pub refresh_interval: u64,
+    // the ics721 contract used to bridge tokens to other chains
+    pub ics721: Option<Addr>,
+    // the default seconds before a bridge transfer times out
+    pub bridge_timeout: u64,
+    // the limits of ics721 implementations the collections and tokens are checked against
+    pub ics721_policy: Option<Ics721Policy>,
+    // the seconds between the proposal and the execution of a recovery
+    pub recovery_timelock: u64,
 }
 
+/// the limits of ics721 implementations on names, symbols and token ids
 #[cw_serde]
-pub struct Trait {
-    pub display_type: Option<String>,
-    pub trait_type: String,
-    pub value: String,
+pub struct Ics721Policy {
+    pub max_token_id_length: u32,
+    // the characters allowed in token ids besides ascii alphanumerics
+    pub token_id_extra_chars: String,
+    pub max_name_length: u32,
+    // symbols only contain ascii alphanumerics
+    pub max_symbol_length: u32,
 }
 
 #[cw_serde]
@@ -56,6 +95,164 @@ pub struct WrapData {
     pub mirrored_collection: Addr,
     pub mirrored_data: MirroredData,
     pub active: bool,
+    // the code id the mirrored collection is running
+    pub code_id: u64,
+    // the wasm admin of the mirrored collection
+    pub admin: Option<Addr>,
+    // the number of original tokens escrowed for this mirror
+    pub escrowed: u64,
+    // the last mirrored token id issued by the sequential strategy
+    pub token_id_sequence: u64,
+    // the collection is revealed manually by the controller
+    pub revealed: bool,
+    // the number of mirrored tokens currently sent to other chains
+    pub bridged_out: u64,
+    // the cross-chain origin of the original collection if it is an ics721 voucher
+    pub provenance: Option<Provenance>,
+}
+
+/// the cross-chain origin of an ics721 voucher collection
+#[cw_serde]
+pub struct Provenance {
+    // the full class id, prefixed by the ports and channels the collection came through
+    pub class_id: String,
+    // the class id on the chain the collection was created
+    pub base_class_id: String,
+    // the local channel the collection was received through
+    pub channel_id: String,
+    // the chain the collection was received from, only the last hop of a multi-hop trace,
+    // none if the channel is not registered
+    pub previous_chain: Option<String>,
+    // the number of hops of the trace, the collection was created on the previous chain if 1
+    pub hops: u32,
+}
+
+/// the counterparty of a channel used to bridge mirrored tokens
+#[cw_serde]
+pub struct ChannelInfo {
+    pub chain_id: String,
+    // the bech32 prefix of the addresses of the counterparty chain, receivers are not checked if none
+    pub bech32_prefix: Option<String>,
+    // a receiver with the local prefix is converted to the counterparty prefix instead of rejected
+    pub convert_local_receiver: bool,
+}
+
+/// the maximum number of mirrored tokens bridged out through a channel in a fixed window
+#[cw_serde]
+pub struct RateLimit {
+    pub max_tokens: u64,
+    pub window_seconds: u64,
+    // the start of the current window
+    pub window_start: Timestamp,
+    // the number of mirrored tokens bridged out in the current window and not returned in it
+    pub used: u64,
+}
+
+impl RateLimit {
+    /// the rate limit with the window containing the given time
+    pub fn at(mut self, now: Timestamp) -> Self {
+        if now >= self.window_start.plus_seconds(self.window_seconds) {
+            self.window_start = now;
+            self.used = 0;
+        }
+        self
+    }
+}
+
+/// the bridge lifecycle of a mirrored token
+#[cw_serde]
+pub enum BridgeState {
+    Wrapped,
+    /// sent to the ics721 contract, waiting for the acknowledgement
+    InFlight {
+        channel: String,
+        // the counterparty chain of the channel when the token was sent
+        chain: String,
+        owner: Addr,
+        // the start of the rate limit window the token is counted in, none if not limited
+        window_start: Option<Timestamp>,
+    },
+    Bridged {
+        chain: String,
+        channel: String,
+        window_start: Option<Timestamp>,
+    },
+    /// the token came back from the other chain, or its transfer failed and it is returned to its owner
+    Returned,
+}
+
+impl BridgeState {
+    pub fn status(&self) -> BridgeStatus {
+        match self {
+            BridgeState::Wrapped => BridgeStatus::Wrapped,
+            BridgeState::InFlight { .. } => BridgeStatus::InFlight,
+            BridgeState::Bridged { .. } => BridgeStatus::Bridged,
+            BridgeState::Returned => BridgeStatus::Returned,
+        }
+    }
+}
+
+/// the bridge state of a mirrored token without its data
+#[cw_serde]
+pub enum BridgeStatus {
+    Wrapped,
+    InFlight,
+    Bridged,
+    Returned,
+}
+
+impl BridgeStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BridgeStatus::Wrapped => "wrapped",
+            BridgeStatus::InFlight => "in_flight",
+            BridgeStatus::Bridged => "bridged",
+            BridgeStatus::Returned => "returned",
+        }
+    }
+}
+
+/// the indexes of the bridge states of mirrored tokens
+pub struct BridgeStateIndexes<'a> {
+    // the mirrored tokens in a bridge status, ordered by wrap data index and mirrored token id
+    pub status: MultiIndex<'a, String, BridgeState, (u64, &'a str)>,
+}
+
+impl<'a> IndexList<BridgeState> for BridgeStateIndexes<'a> {
+    fn get_indexes(&'_ self) -> Box<dyn Iterator<Item = &'_ dyn Index<BridgeState>> + '_> {
+        let v: Vec<&dyn Index<BridgeState>> = vec![&self.status];
+        Box::new(v.into_iter())
+    }
+}
+
+fn bridge_status_index(_pk: &[u8], state: &BridgeState) -> String {
+    state.status().as_str().to_string()
+}
+
+/// the escrow record of an original token held by the wrapper
+#[cw_serde]
+pub struct Escrow {
+    // the wrap data index of the mirror the token is wrapped into
+    pub wrap_data_index: u64,
+    // the owner of the original token when it was wrapped
+    pub owner: Addr,
+    // the receiver of the mirrored token
+    pub recipient: Addr,
+    pub status: EscrowStatus,
+}
+
+#[cw_serde]
+pub enum EscrowStatus {
+    Escrowed,
+    /// the original token was released by a recovery while its mirrored token was stranded
+    Recovered,
+}
+
+/// a release of the original token of a stranded mirrored token, executable after the timelock
+#[cw_serde]
+pub struct Recovery {
+    pub recipient: Addr,
+    pub executable_at: Timestamp,
 }
 
 /// the information of mirrored data
@@ -65,6 +262,37 @@ pub struct MirroredData {
     pub collection_name: Option<String>,
     pub collection_symbol: Option<String>,
     pub base_uri: Option<String>,
+    pub token_id_strategy: Option<TokenIdStrategy>,
+    // the template of token uri, see `token_uri::render_token_uri_template`
+    pub token_uri_template: Option<String>,
+    // the mirrored tokens are minted with a placeholder uri until revealed
+    pub reveal: Option<RevealConfig>,
+    // the royalty of the mirrored collection, carried in its ics721 class data
+    pub royalty_info: Option<RoyaltyInfo>,
+}
+
+/// the delayed reveal of a mirrored collection
+#[cw_serde]
+pub struct RevealConfig {
+    // the token uri of all mirrored tokens before reveal
+    pub placeholder_uri: String,
+    // the collection is revealed automatically at this time, otherwise only by the controller
+    pub reveal_at: Option<Timestamp>,
+}
+
+/// the strategy to derive the mirrored token id from the original token id
+#[cw_serde]
+#[derive(Default)]
+pub enum TokenIdStrategy {
+    /// the mirrored token id is the original token id
+    #[default]
+    Identity,
+    /// the original token id with a prefix
+    Prefixed { prefix: String },
+    /// a counter of the mirror, starting from 1
+    Sequential,
+    /// the hex encoded sha256 hash of the original token id
+    Hashed,
 }
 
 /// This ContractInfoResponse is used for SeekHype
@@ -1,158 +1,341 @@
-// #[cfg(test)]
-// pub mod env {
-//     use cosmwasm_std::{Addr, Coin, Empty, Uint128};
-
-//     use cw_multi_test::{App, AppBuilder, Contract, ContractWrapper, Executor};
-
-//     use crate::contract::{
-//         execute as MinterExecute, instantiate as MinterInstantiate, query as MinterQuery,
-//         reply as MinterReply,
-//     };
-
-//     use price_feed::contract::{
-//         execute as PriceFeedExecute, instantiate as PriceFeedInstantiate, query as PriceFeedQuery,
-//     };
-
-//     use cw20_base::contract::{
-//         execute as Cw20Execute, instantiate as Cw20Instantiate, query as Cw20Query,
-//     };
-
-//     use cw20_base::msg::InstantiateMsg as Cw20InstantiateMsg;
-
-//     use crate::msg::InstantiateMsg as MinterInstantiateMsg;
-//     use price_feed::msg::InstantiateMsg as PriceFeedInstantiateMsg;
-
-//     pub const ADMIN: &str = "aura1000000000000000000000000000000000admin";
-//     pub const USER1: &str = "aura1000000000000000000000000000000000user1";
-//     pub const AURA: &str = "aura10000000000000000000000000000000000aura";
-//     pub const CONTROLLER: &str = "aura10000000000000000000000000000controller";
-//     pub const CONTROLLER_FAKE: &str = "aura10000000000000000000000000000fake";
-
-//     pub const NATIVE_DENOM: &str = "uaura";
-//     pub const NATIVE_BALANCE: u128 = 1_000_000_000_000u128;
-
-//     pub const NATIVE_DENOM_2: &str = "utaura";
-//     pub const NATIVE_BALANCE_2: u128 = 1_000_000_000_000u128;
-
-//     pub struct ContractInfo {
-//         pub contract_addr: String,
-//         pub contract_code_id: u64,
-//     }
-
-//     // create app instance and init balance of NATIVE token for admin
-//     fn mock_app() -> App {
-//         AppBuilder::new().build(|router, _, storage| {
-//             router
-//                 .bank
-//                 .init_balance(
-//                     storage,
-//                     &Addr::unchecked(ADMIN),
-//                     vec![
-//                         Coin {
-//                             denom: NATIVE_DENOM.to_string(),
-//                             amount: Uint128::new(NATIVE_BALANCE),
-//                         },
-//                         Coin {
-//                             denom: NATIVE_DENOM_2.to_string(),
-//                             amount: Uint128::new(NATIVE_BALANCE_2),
-//                         },
-//                     ],
-//                 )
-//                 .unwrap();
-//         })
-//     }
-
-//     // create minter contract
-//     pub fn minter_contract_template() -> Box<dyn Contract<Empty>> {
-//         let contract = ContractWrapper::new(MinterExecute, MinterInstantiate, MinterQuery)
-//             .with_reply(MinterReply);
-//         Box::new(contract)
-//     }
-
-//     // create price feed contract
-//     pub fn price_feed_contract_template() -> Box<dyn Contract<Empty>> {
-//         let contract = ContractWrapper::new(PriceFeedExecute, PriceFeedInstantiate, PriceFeedQuery);
-//         Box::new(contract)
-//     }
-
-//     // create cw20 contract
-//     pub fn cw20_contract_template() -> Box<dyn Contract<Empty>> {
-//         let contract = ContractWrapper::new(Cw20Execute, Cw20Instantiate, Cw20Query);
-//         Box::new(contract)
-//     }
-
-//     pub fn instantiate_contracts() -> (App, Vec<ContractInfo>) {
-//         // Create a new app instance
-//         let mut app = mock_app();
-//         // Create a vector to store all contract info ([halo factory - [0])
-//         let mut contract_info_vec: Vec<ContractInfo> = Vec::new();
-
-//         // store code of all contracts to the app and get the code ids
-//         let minter_contract_code_id = app.store_code(minter_contract_template());
-//         let price_feed_contract_code_id = app.store_code(price_feed_contract_template());
-//         let cw20_contract_code_id = app.store_code(cw20_contract_template());
-
-//         // instantiate price feed contract
-//         let price_feed_contract_addr = app
-//             .instantiate_contract(
-//                 price_feed_contract_code_id,
-//                 Addr::unchecked(ADMIN),
-//                 &PriceFeedInstantiateMsg {
-//                     controller: CONTROLLER.to_string(),
-//                     decimals: 6,
-//                     description: "AURA / VND".to_string(),
-//                 },
-//                 &[],
-//                 "test instantiate contract",
-//                 None,
-//             )
-//             .unwrap();
-//         // add contract info to the vector
-//         contract_info_vec.push(ContractInfo {
-//             contract_addr: price_feed_contract_addr.to_string(),
-//             contract_code_id: price_feed_contract_code_id,
-//         });
-
-//         // instantiate minter contract
-//         let minter_contract_addr = app
-//             .instantiate_contract(
-//                 minter_contract_code_id,
-//                 Addr::unchecked(ADMIN),
-//                 &MinterInstantiateMsg {
-//                     receiver_name: "aura".to_string(),
-//                     receiver_address: AURA.to_string(),
-//                     accepted_denom: NATIVE_DENOM.to_string(),
-//                     price_feed: price_feed_contract_addr.to_string(),
-//                     token_code_id: cw20_contract_code_id,
-//                     token_instantiation_msg: Cw20InstantiateMsg {
-//                         name: "Stable Token".to_string(),
-//                         symbol: "STV".to_string(),
-//                         decimals: 6,
-//                         initial_balances: vec![],
-//                         mint: None,
-//                         marketing: None,
-//                     },
-//                 },
-//                 &[],
-//                 "test instantiate contract",
-//                 None,
-//             )
-//             .unwrap();
-
-//         // add contract info to the vector
-//         contract_info_vec.push(ContractInfo {
-//             contract_addr: minter_contract_addr.to_string(),
-//             contract_code_id: minter_contract_code_id,
-//         });
-
-//         (app, contract_info_vec)
-//     }
-
-//     #[test]
-//     fn test_instantiate_contracts() {
-//         let (_app, contract_info_vec) = instantiate_contracts();
-
-//         // check if all contracts are instantiated
-//         assert_eq!(contract_info_vec.len(), 2);
-//     }
-// }
+#[cfg(test)]
+pub mod env {
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{
+        to_json_binary, Addr, Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdResult,
+    };
+    use cw721::Cw721ReceiveMsg;
+    use cw721_base::{
+        ContractError as Cw721ContractError, Cw721Contract, ExecuteMsg as Cw721ExecuteMsg,
+        InstantiateMsg as Cw721InstantiateMsg, QueryMsg as Cw721QueryMsg,
+    };
+    use cw_multi_test::{App, Contract, ContractWrapper, Executor};
+    use cw_storage_plus::{Item, Map};
+    use mirrored_cw721::contract::{
+        execute as MirroredExecute, instantiate as MirroredInstantiate, migrate as MirroredMigrate,
+        query as MirroredQuery,
+    };
+    use mirrored_cw721::msg::{QueryExt, QueryMsg as MirroredQueryMsg};
+    use mirrored_cw721::state::MirrorInfo;
+
+    use crate::contract::{
+        execute as WrapperExecute, instantiate as WrapperInstantiate, query as WrapperQuery,
+        reply as WrapperReply, sudo as WrapperSudo,
+    };
+    use crate::msg::{ExecuteMsg as WrapperExecuteMsg, InstantiateMsg as WrapperInstantiateMsg};
+    use crate::state::{Extension, MirroredData};
+
+    pub const ADMIN: &str = "aura1000000000000000000000000000000000admin";
+    pub const USER1: &str = "aura1000000000000000000000000000000000user1";
+    pub const USER2: &str = "aura1000000000000000000000000000000000user2";
+    pub const CONTROLLER: &str = "aura10000000000000000000000000000controller";
+
+    // the messages of a contract receiving tokens by `SendNft`,
+    // which also mocks the class id query of the ics721 contract
+    #[cw_serde]
+    pub enum ReceiverExecuteMsg {
+        ReceiveNft(Cw721ReceiveMsg),
+        SetClassId { contract: String, class_id: String },
+    }
+
+    #[cw_serde]
+    pub enum ReceiverQueryMsg {
+        Received {},
+        ClassId { contract: String },
+    }
+
+    // the tokens received by the receiver contract
+    const RECEIVED: Item<Vec<Cw721ReceiveMsg>> = Item::new("received");
+
+    // the ics721 class ids of the collections, keyed by collection address
+    const CLASS_IDS: Map<&str, String> = Map::new("class-ids");
+
+    pub struct ContractInfo {
+        pub contract_addr: String,
+        pub contract_code_id: u64,
+    }
+
+    // the original collection is a cw721-base contract with metadata
+    pub type OriginalCw721Contract<'a> = Cw721Contract<'a, Extension, Empty, Empty, Empty>;
+
+    // a plain cw721-base contract, which can be used as mirrored collection with a custom template
+    pub type BaseCw721Contract<'a> = Cw721Contract<'a, Option<Empty>, Empty, Empty, Empty>;
+
+    fn original_instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721InstantiateMsg,
+    ) -> StdResult<Response> {
+        OriginalCw721Contract::default().instantiate(deps, env, info, msg)
+    }
+
+    fn original_execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721ExecuteMsg<Extension, Empty>,
+    ) -> Result<Response, Cw721ContractError> {
+        OriginalCw721Contract::default().execute(deps, env, info, msg)
+    }
+
+    fn original_query(deps: Deps, env: Env, msg: Cw721QueryMsg<Empty>) -> StdResult<Binary> {
+        OriginalCw721Contract::default().query(deps, env, msg)
+    }
+
+    fn base_instantiate(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721InstantiateMsg,
+    ) -> StdResult<Response> {
+        BaseCw721Contract::default().instantiate(deps, env, info, msg)
+    }
+
+    fn base_execute(
+        deps: DepsMut,
+        env: Env,
+        info: MessageInfo,
+        msg: Cw721ExecuteMsg<Option<Empty>, Empty>,
+    ) -> Result<Response, Cw721ContractError> {
+        BaseCw721Contract::default().execute(deps, env, info, msg)
+    }
+
+    fn base_query(deps: Deps, env: Env, msg: Cw721QueryMsg<Empty>) -> StdResult<Binary> {
+        BaseCw721Contract::default().query(deps, env, msg)
+    }
+
+    fn receiver_instantiate(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        _msg: Empty,
+    ) -> StdResult<Response> {
+        RECEIVED.save(deps.storage, &vec![])?;
+        Ok(Response::new())
+    }
+
+    fn receiver_execute(
+        deps: DepsMut,
+        _env: Env,
+        _info: MessageInfo,
+        msg: ReceiverExecuteMsg,
+    ) -> StdResult<Response> {
+        match msg {
+            ReceiverExecuteMsg::ReceiveNft(receive_msg) => {
+                RECEIVED.update(deps.storage, |mut received| -> StdResult<_> {
+                    received.push(receive_msg);
+                    Ok(received)
+                })?;
+            }
+            ReceiverExecuteMsg::SetClassId { contract, class_id } => {
+                CLASS_IDS.save(deps.storage, &contract, &class_id)?;
+            }
+        }
+        Ok(Response::new())
+    }
+
+    fn receiver_query(deps: Deps, _env: Env, msg: ReceiverQueryMsg) -> StdResult<Binary> {
+        match msg {
+            ReceiverQueryMsg::Received {} => to_json_binary(&RECEIVED.load(deps.storage)?),
+            ReceiverQueryMsg::ClassId { contract } => {
+                to_json_binary(&CLASS_IDS.may_load(deps.storage, &contract)?)
+            }
+        }
+    }
+
+    fn base_migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
+        Ok(Response::new())
+    }
+
+    // create wrapper contract
+    pub fn wrapper_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(WrapperExecute, WrapperInstantiate, WrapperQuery)
+            .with_reply(WrapperReply)
+            .with_sudo(WrapperSudo);
+        Box::new(contract)
+    }
+
+    // create original cw721 contract
+    pub fn original_cw721_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(original_execute, original_instantiate, original_query);
+        Box::new(contract)
+    }
+
+    // create mirrored cw721 contract
+    pub fn mirrored_cw721_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(MirroredExecute, MirroredInstantiate, MirroredQuery)
+            .with_migrate(MirroredMigrate);
+        Box::new(contract)
+    }
+
+    // create cw721-base contract
+    pub fn base_cw721_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(base_execute, base_instantiate, base_query)
+            .with_migrate(base_migrate);
+        Box::new(contract)
+    }
+
+    // create contract receiving tokens by `SendNft`
+    pub fn receiver_contract_template() -> Box<dyn Contract<Empty>> {
+        let contract = ContractWrapper::new(receiver_execute, receiver_instantiate, receiver_query);
+        Box::new(contract)
+    }
+
+    // instantiate a contract receiving tokens by `SendNft`
+    pub fn instantiate_receiver(app: &mut App) -> String {
+        let receiver_contract_code_id = app.store_code(receiver_contract_template());
+        app.instantiate_contract(
+            receiver_contract_code_id,
+            Addr::unchecked(ADMIN),
+            &Empty {},
+            &[],
+            "test instantiate contract",
+            None,
+        )
+        .unwrap()
+        .to_string()
+    }
+
+    pub fn instantiate_contracts() -> (App, Vec<ContractInfo>) {
+        // Create a new app instance
+        let mut app = App::default();
+        // Create a vector to store all contract info
+        // ([wrapper - [0], original collection - [1], mirrored collection - [2], ics721 - [3])
+        let mut contract_info_vec: Vec<ContractInfo> = Vec::new();
+
+        // store code of all contracts to the app and get the code ids
+        let wrapper_contract_code_id = app.store_code(wrapper_contract_template());
+        let original_cw721_contract_code_id = app.store_code(original_cw721_contract_template());
+        let mirrored_cw721_contract_code_id = app.store_code(mirrored_cw721_contract_template());
+
+        // the ics721 contract is mocked by a receiver recording the bridged tokens
+        let ics721_contract_code_id = app.store_code(receiver_contract_template());
+        let ics721_contract_addr = app
+            .instantiate_contract(
+                ics721_contract_code_id,
+                Addr::unchecked(ADMIN),
+                &Empty {},
+                &[],
+                "test instantiate contract",
+                None,
+            )
+            .unwrap();
+
+        // instantiate wrapper contract
+        let wrapper_contract_addr = app
+            .instantiate_contract(
+                wrapper_contract_code_id,
+                Addr::unchecked(ADMIN),
+                &WrapperInstantiateMsg {
+                    controller: CONTROLLER.to_string(),
+                    cw721_code_id: mirrored_cw721_contract_code_id,
+                    wrapper_as_mirror_admin: None,
+                    refresh_interval: None,
+                    ics721: Some(ics721_contract_addr.to_string()),
+                    bridge_timeout: None,
+                    ics721_policy: None,
+                    recovery_timelock: None,
+                },
+                &[],
+                "test instantiate contract",
+                None,
+            )
+            .unwrap();
+        contract_info_vec.push(ContractInfo {
+            contract_addr: wrapper_contract_addr.to_string(),
+            contract_code_id: wrapper_contract_code_id,
+        });
+
+        // instantiate original collection
+        let original_contract_addr = app
+            .instantiate_contract(
+                original_cw721_contract_code_id,
+                Addr::unchecked(ADMIN),
+                &Cw721InstantiateMsg {
+                    name: "Original Collection".to_string(),
+                    symbol: "ORIGINAL".to_string(),
+                    minter: ADMIN.to_string(),
+                },
+                &[],
+                "test instantiate contract",
+                None,
+            )
+            .unwrap();
+        contract_info_vec.push(ContractInfo {
+            contract_addr: original_contract_addr.to_string(),
+            contract_code_id: original_cw721_contract_code_id,
+        });
+
+        // register the original collection, the mirrored collection is instantiated in reply
+        let res = app
+            .execute_contract(
+                Addr::unchecked(CONTROLLER),
+                wrapper_contract_addr,
+                &WrapperExecuteMsg::RegisterCollection {
+                    original_collection: original_contract_addr.to_string(),
+                    new_collection: MirroredData {
+                        collection_name: None,
+                        collection_symbol: None,
+                        base_uri: None,
+                        token_id_strategy: None,
+                        token_uri_template: None,
+                        reveal: None,
+                        royalty_info: None,
+                    },
+                    instantiate_options: None,
+                },
+                &[],
+            )
+            .unwrap();
+        let mirrored_contract_addr = res
+            .events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == "mirrored_collection")
+            .map(|attribute| attribute.value.clone())
+            .unwrap();
+        contract_info_vec.push(ContractInfo {
+            contract_addr: mirrored_contract_addr,
+            contract_code_id: mirrored_cw721_contract_code_id,
+        });
+        contract_info_vec.push(ContractInfo {
+            contract_addr: ics721_contract_addr.to_string(),
+            contract_code_id: ics721_contract_code_id,
+        });
+
+        (app, contract_info_vec)
+    }
+
+    #[test]
+    fn test_instantiate_contracts() {
+        let (app, contract_info_vec) = instantiate_contracts();
+
+        // check if all contracts are instantiated
+        assert_eq!(contract_info_vec.len(), 4);
+
+        // check if the mirrored collection is instantiated with the code id of the config
+        let contract_info = app
+            .wrap()
+            .query_wasm_contract_info(&contract_info_vec[2].contract_addr)
+            .unwrap();
+        assert_eq!(contract_info.code_id, contract_info_vec[2].contract_code_id);
+        assert_eq!(contract_info.creator, contract_info_vec[0].contract_addr);
+
+        // the mirrored collection knows the wrapper and its original collection
+        let mirror_info: MirrorInfo = app
+            .wrap()
+            .query_wasm_smart(
+                &contract_info_vec[2].contract_addr,
+                &MirroredQueryMsg::Extension {
+                    msg: QueryExt::MirrorInfo {},
+                },
+            )
+            .unwrap();
+        assert_eq!(mirror_info.wrapper, contract_info_vec[0].contract_addr);
+        assert_eq!(
+            mirror_info.original_collection,
+            contract_info_vec[1].contract_addr
+        );
+    }
+}
@@ -1,167 +1,3782 @@
-// #![cfg(test)]
-// mod tests {
-//     use crate::msg::ExecuteMsg;
-//     use crate::tests::env_setup::env::{
-//         instantiate_contracts, ADMIN, AURA, CONTROLLER, CONTROLLER_FAKE, NATIVE_DENOM, USER1,
-//     };
-//     use cosmwasm_std::{coins, Addr, Uint128};
-//     use cw_multi_test::Executor;
-//     use price_feed::msg::{
-//         ExecuteMsg as PriceFeedExecuteMsg, QueryMsg as PriceFeedQueryMsg, RoundDataResponse,
-//     };
-
-//     mod price_feed_testing {
-//         use super::*;
-
-//         #[test]
-//         fn only_owner_can_change_controller() {
-//             // get integration test app and contracts
-//             let (mut app, contracts) = instantiate_contracts();
-//             let price_feed_contract_addr = &contracts[0].contract_addr;
-
-//             // prepare the update controller message
-//             let update_controller_msg = PriceFeedExecuteMsg::UpdateController {
-//                 controller: CONTROLLER_FAKE.to_string(),
-//             };
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(USER1),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_controller_msg,
-//                 &[],
-//             );
-//             assert!(res.is_err());
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(ADMIN),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_controller_msg,
-//                 &[],
-//             );
-//             assert!(res.is_ok());
-//         }
-
-//         #[test]
-//         fn only_controller_can_update_answer() {
-//             // get integration test app and contracts
-//             let (mut app, contracts) = instantiate_contracts();
-//             let price_feed_contract_addr = &contracts[0].contract_addr;
-
-//             // prepare the update controller message
-//             let update_answer_msg = PriceFeedExecuteMsg::UpdateRoundData { answer: 100000u64 };
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(USER1),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_answer_msg,
-//                 &[],
-//             );
-//             assert!(res.is_err());
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(ADMIN),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_answer_msg,
-//                 &[],
-//             );
-//             assert!(res.is_err());
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(CONTROLLER),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_answer_msg,
-//                 &[],
-//             );
-//             assert!(res.is_ok());
-
-//             // query the price feed contract to check if the answer is updated
-//             let query_msg = PriceFeedQueryMsg::LastestRoundData {};
-
-//             let res: RoundDataResponse = app
-//                 .wrap()
-//                 .query_wasm_smart(price_feed_contract_addr, &query_msg)
-//                 .unwrap();
-//             assert_eq!(res.answer, Uint128::from(100000u64));
-//         }
-//     }
-
-//     mod minter {
-//         use crate::msg::{ExchangingInfoResponse, QueryMsg};
-
-//         use super::*;
-
-//         #[test]
-//         fn user_can_exchange_token() {
-//             // get integration test app and contracts
-//             let (mut app, contracts) = instantiate_contracts();
-//             let minter_contract_addr = &contracts[1].contract_addr;
-//             let price_feed_contract_addr = &contracts[0].contract_addr;
-
-//             // prepare the update controller message
-//             let update_answer_msg = PriceFeedExecuteMsg::UpdateRoundData {
-//                 answer: 10000000u64,
-//             };
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(CONTROLLER),
-//                 Addr::unchecked(price_feed_contract_addr),
-//                 &update_answer_msg,
-//                 &[],
-//             );
-//             assert!(res.is_ok());
-
-//             // ADMIN send 50 native token to USER1
-//             let res = app.send_tokens(
-//                 Addr::unchecked(ADMIN),
-//                 Addr::unchecked(USER1),
-//                 &coins(50, NATIVE_DENOM),
-//             );
-//             assert!(res.is_ok());
-
-//             // prepare the exchange message
-//             let exchange_msg = ExecuteMsg::Exchange {
-//                 amount: Uint128::from(50u64),
-//                 expected_received: Uint128::from(500000000u64),
-//             };
-
-//             let res = app.execute_contract(
-//                 Addr::unchecked(USER1),
-//                 Addr::unchecked(minter_contract_addr),
-//                 &exchange_msg,
-//                 &coins(50, NATIVE_DENOM),
-//             );
-//             assert!(res.is_ok());
-
-//             // query information of token_address of minter contract
-//             let query_msg = QueryMsg::ExchangingInfo {};
-//             let exchanging_info_res: ExchangingInfoResponse = app
-//                 .wrap()
-//                 .query_wasm_smart(minter_contract_addr, &query_msg)
-//                 .unwrap();
-
-//             // query balance of USER1 in the token_address
-//             let query_msg = cw20_base::msg::QueryMsg::Balance {
-//                 address: USER1.to_string(),
-//             };
-//             let res: cw20::BalanceResponse = app
-//                 .wrap()
-//                 .query_wasm_smart(exchanging_info_res.token_address.clone(), &query_msg)
-//                 .unwrap();
-
-//             // the balance should be 0
-//             assert_eq!(res.balance, Uint128::zero());
-
-//             // query balance of AURA in the token_address
-//             let query_msg = cw20_base::msg::QueryMsg::Balance {
-//                 address: AURA.to_string(),
-//             };
-//             let res: cw20::BalanceResponse = app
-//                 .wrap()
-//                 .query_wasm_smart(exchanging_info_res.token_address, &query_msg)
-//                 .unwrap();
-
-//             // the balance should be 500000000
-//             assert_eq!(res.balance, Uint128::from(500000000u64));
-//         }
-//     }
-// }
+#![cfg(test)]
+mod tests {
+    use crate::contract::{DEFAULT_BRIDGE_TIMEOUT, DEFAULT_RECOVERY_TIMELOCK};
+    use crate::error::ContractError;
+    use crate::ics721::{
+        IbcOutgoingMsg, Ics721AckCallbackMsg, Ics721Status, NonFungibleTokenPacketData,
+    };
+    use crate::msg::{
+        ExecuteMsg, InstantiateOptions, PostAction, PostActionMsg, QueryMsg, ReceiveMsg, SudoMsg,
+        TokenIdCheckResponse, TokenLinkResponse,
+    };
+    use crate::state::{
+        BridgeState, BridgeStatus, ChannelInfo, Escrow, EscrowStatus, Extension, Ics721Policy,
+        Metadata, MirroredData, Provenance, RateLimit, Recovery, RevealConfig, RoyaltyInfo,
+        TokenIdStrategy, WrapData,
+    };
+    use crate::tests::env_setup::env::{
+        base_cw721_contract_template, instantiate_contracts, instantiate_receiver,
+        mirrored_cw721_contract_template, ContractInfo, ReceiverExecuteMsg, ReceiverQueryMsg,
+        ADMIN, CONTROLLER, USER1, USER2,
+    };
+    use cosmwasm_std::{from_json, to_json_binary, Addr, Empty, IbcTimeout, StdResult};
+    use cw721::{
+        ContractInfoResponse, Cw721QueryMsg, Cw721ReceiveMsg, NftInfoResponse, OwnerOfResponse,
+    };
+    use cw721_base::{
+        ExecuteMsg as Cw721ExecuteMsg, InstantiateMsg as Cw721InstantiateMsg, MinterResponse,
+        QueryMsg as Cw721BaseQueryMsg,
+    };
+    use cw_multi_test::error::AnyResult;
+    use cw_multi_test::{App, AppResponse, Executor};
+    use mirrored_cw721::msg::ClassData;
+
+    // register the original collection once more, returning the new mirrored collection
+    fn register_mirror(
+        app: &mut App,
+        contracts: &[ContractInfo],
+        new_collection: MirroredData,
+    ) -> String {
+        let res = app
+            .execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RegisterCollection {
+                    original_collection: contracts[1].contract_addr.clone(),
+                    new_collection,
+                    instantiate_options: None,
+                },
+                &[],
+            )
+            .unwrap();
+        attribute(&res, "mirrored_collection").unwrap()
+    }
+
+    // instantiate another original collection and register it, returning the mirrored collection
+    fn register_collection(
+        app: &mut App,
+        contracts: &[ContractInfo],
+        instantiate_options: Option<InstantiateOptions>,
+    ) -> AnyResult<String> {
+        let original_contract_addr = app
+            .instantiate_contract(
+                contracts[1].contract_code_id,
+                Addr::unchecked(ADMIN),
+                &Cw721InstantiateMsg {
+                    name: "Another Collection".to_string(),
+                    symbol: "ANOTHER".to_string(),
+                    minter: ADMIN.to_string(),
+                },
+                &[],
+                "test instantiate contract",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(CONTROLLER),
+            Addr::unchecked(&contracts[0].contract_addr),
+            &ExecuteMsg::RegisterCollection {
+                original_collection: original_contract_addr.to_string(),
+                new_collection: MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: None,
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+                instantiate_options,
+            },
+            &[],
+        )
+        .map(|res| attribute(&res, "mirrored_collection").unwrap())
+    }
+
+    // the value of an attribute of the response
+    fn attribute(res: &AppResponse, key: &str) -> Option<String> {
+        res.events
+            .iter()
+            .flat_map(|event| event.attributes.iter())
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.clone())
+    }
+
+    // mint an original token to the owner and let the wrapper transfer it
+    fn mint_original(app: &mut App, contracts: &[ContractInfo], owner: &str, token_id: &str) {
+        let original_contract_addr = &contracts[1].contract_addr;
+
+        app.execute_contract(
+            Addr::unchecked(ADMIN),
+            Addr::unchecked(original_contract_addr),
+            &Cw721ExecuteMsg::<Extension, Empty>::Mint {
+                token_id: token_id.to_string(),
+                owner: owner.to_string(),
+                token_uri: Some(format!("ipfs://original/{}", token_id)),
+                extension: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        app.execute_contract(
+            Addr::unchecked(owner),
+            Addr::unchecked(original_contract_addr),
+            &Cw721ExecuteMsg::<Extension, Empty>::ApproveAll {
+                operator: contracts[0].contract_addr.clone(),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    fn wrap(
+        app: &mut App,
+        contracts: &[ContractInfo],
+        sender: &str,
+        token_id: &str,
+    ) -> AnyResult<AppResponse> {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            Addr::unchecked(&contracts[0].contract_addr),
+            &ExecuteMsg::Wrap {
+                collection_address: contracts[1].contract_addr.clone(),
+                token_ids: vec![token_id.to_string()],
+                mirror_index: None,
+                recipient: None,
+                owner: None,
+                then: None,
+            },
+            &[],
+        )
+    }
+
+    // the wrapper burns the mirrored token without being approved
+    fn unwrap(
+        app: &mut App,
+        contracts: &[ContractInfo],
+        sender: &str,
+        token_id: &str,
+    ) -> AnyResult<AppResponse> {
+        app.execute_contract(
+            Addr::unchecked(sender),
+            Addr::unchecked(&contracts[0].contract_addr),
+            &ExecuteMsg::Unwrap {
+                collection_address: contracts[2].contract_addr.clone(),
+                token_ids: vec![token_id.to_string()],
+                recipient: None,
+                owner: None,
+                then: None,
+            },
+            &[],
+        )
+    }
+
+    fn owner_of(app: &App, collection_address: &str, token_id: &str) -> Option<String> {
+        app.wrap()
+            .query_wasm_smart::<OwnerOfResponse>(
+                collection_address,
+                &Cw721QueryMsg::OwnerOf {
+                    token_id: token_id.to_string(),
+                    include_expired: None,
+                },
+            )
+            .ok()
+            .map(|res| res.owner)
+    }
+
+    mod wrap_and_unwrap {
+        use super::*;
+
+        #[test]
+        fn wrap_escrows_the_original_token_and_unwrap_releases_it() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+            let original_contract_addr = &contracts[1].contract_addr;
+            let mirrored_contract_addr = &contracts[2].contract_addr;
+
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // only the owner of the original token can wrap it
+            let res = wrap(&mut app, &contracts, USER2, "1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            // the original token is escrowed by the wrapper and the mirrored token is minted
+            assert_eq!(
+                owner_of(&app, original_contract_addr, "1"),
+                Some(wrapper_contract_addr.clone())
+            );
+            assert_eq!(
+                owner_of(&app, mirrored_contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+
+            unwrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            // the mirrored token is burnt and the original token is back to its owner
+            assert_eq!(
+                owner_of(&app, original_contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+            assert_eq!(owner_of(&app, mirrored_contract_addr, "1"), None);
+        }
+
+        // mint an original token with metadata to USER1 and let the wrapper transfer it
+        fn mint_original_with_metadata(app: &mut App, contracts: &[ContractInfo]) -> Extension {
+            let extension = Some(Metadata {
+                name: Some("Token 1".to_string()),
+                image: Some("ipfs://image/1".to_string()),
+                ..Metadata::default()
+            });
+
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::Mint {
+                    token_id: "1".to_string(),
+                    owner: USER1.to_string(),
+                    token_uri: None,
+                    extension: extension.clone(),
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::Approve {
+                    spender: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            extension
+        }
+
+        #[test]
+        fn mirrored_token_carries_the_metadata_of_the_original_token() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let extension = mint_original_with_metadata(&mut app, &contracts);
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let nft_info: NftInfoResponse<Extension> = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[2].contract_addr,
+                    &Cw721QueryMsg::NftInfo {
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(nft_info.extension, extension);
+        }
+
+        #[test]
+        fn cw721_base_mirror_is_minted_without_metadata() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original_with_metadata(&mut app, &contracts);
+
+            // a plain cw721-base mirror rejects the metadata extension
+            let code_id = app.store_code(base_cw721_contract_template());
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::RegisterCollection {
+                        original_collection: contracts[1].contract_addr.clone(),
+                        new_collection: MirroredData {
+                            collection_name: None,
+                            collection_symbol: None,
+                            base_uri: None,
+                            token_id_strategy: None,
+                            token_uri_template: None,
+                            reveal: None,
+                            royalty_info: None,
+                        },
+                        instantiate_options: Some(InstantiateOptions {
+                            admin: None,
+                            label: None,
+                            code_id: Some(code_id),
+                            msg_template: Some(
+                                r#"{"name":{name},"symbol":{symbol},"minter":{minter}}"#
+                                    .to_string(),
+                            ),
+                        }),
+                    },
+                    &[],
+                )
+                .unwrap();
+            let mirrored_contract_addr = attribute(&res, "mirrored_collection").unwrap();
+
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let nft_info: NftInfoResponse<Option<Empty>> = app
+                .wrap()
+                .query_wasm_smart(
+                    &mirrored_contract_addr,
+                    &Cw721QueryMsg::NftInfo {
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(nft_info.extension, Some(Empty {}));
+        }
+    }
+
+    mod sudo {
+        use super::*;
+
+        #[test]
+        fn governance_can_replace_the_controller() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            app.wasm_sudo(
+                Addr::unchecked(wrapper_contract_addr),
+                &SudoMsg::UpdateController {
+                    controller: USER2.to_string(),
+                },
+            )
+            .unwrap();
+
+            let controller: String = app
+                .wrap()
+                .query_wasm_smart(wrapper_contract_addr, &QueryMsg::Controller {})
+                .unwrap();
+            assert_eq!(controller, USER2);
+
+            // the replaced controller is no longer allowed
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::UpdateController {
+                    controller: CONTROLLER.to_string(),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn pause_stops_wrap_and_unwrap() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            mint_original(&mut app, &contracts, USER1, "1");
+            mint_original(&mut app, &contracts, USER1, "2");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            // only the controller or the governance can pause the contract
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::SetPaused { paused: true },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+            app.wasm_sudo(
+                Addr::unchecked(wrapper_contract_addr),
+                &SudoMsg::SetPaused { paused: true },
+            )
+            .unwrap();
+
+            let res = wrap(&mut app, &contracts, USER1, "2");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Paused {}.to_string()
+            );
+            let res = unwrap(&mut app, &contracts, USER1, "1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Paused {}.to_string()
+            );
+
+            // the controller resumes the contract
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::SetPaused { paused: false },
+                &[],
+            )
+            .unwrap();
+            wrap(&mut app, &contracts, USER1, "2").unwrap();
+            unwrap(&mut app, &contracts, USER1, "1").unwrap();
+        }
+
+        #[test]
+        fn deactivated_collection_cannot_be_wrapped() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            mint_original(&mut app, &contracts, USER1, "1");
+            app.wasm_sudo(
+                Addr::unchecked(&contracts[0].contract_addr),
+                &SudoMsg::SetCollectionStatus {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    active: false,
+                },
+            )
+            .unwrap();
+
+            let res = wrap(&mut app, &contracts, USER1, "1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::CollectionDeactivated {}.to_string()
+            );
+        }
+
+        #[test]
+        fn force_unwrap_releases_the_original_token_to_the_recipient() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            app.wasm_sudo(
+                Addr::unchecked(&contracts[0].contract_addr),
+                &SudoMsg::ForceUnwrap {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: USER2.to_string(),
+                },
+            )
+            .unwrap();
+
+            // the mirrored token is burnt so it cannot be unwrapped again
+            assert_eq!(owner_of(&app, &contracts[2].contract_addr, "1"), None);
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER2.to_string())
+            );
+        }
+    }
+
+    mod migrate_mirrors {
+        use super::*;
+
+        #[test]
+        fn only_mirrors_administrated_by_the_wrapper_are_migrated() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            // the mirrors registered from now on are administrated by the wrapper
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::UpdateConfig {
+                    cw721_code_id: None,
+                    wrapper_as_mirror_admin: Some(true),
+                    refresh_interval: None,
+                    ics721: None,
+                    bridge_timeout: None,
+                    recovery_timelock: None,
+                },
+                &[],
+            )
+            .unwrap();
+            let mirrored_contract_addr = register_collection(&mut app, &contracts, None).unwrap();
+            let contract_info = app
+                .wrap()
+                .query_wasm_contract_info(&mirrored_contract_addr)
+                .unwrap();
+            assert_eq!(contract_info.admin, Some(wrapper_contract_addr.clone()));
+
+            let new_code_id = app.store_code(mirrored_cw721_contract_template());
+            let migrate_mirrors_msg = ExecuteMsg::MigrateMirrors {
+                code_id: new_code_id,
+                msg: to_json_binary(&Empty {}).unwrap(),
+                start_after: None,
+                limit: None,
+            };
+
+            // only the controller can migrate the mirrors
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &migrate_mirrors_msg,
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &migrate_mirrors_msg,
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(
+                attribute(&res, "mirrored_collections"),
+                Some(mirrored_contract_addr.clone())
+            );
+            assert_eq!(attribute(&res, "last_index"), Some("2".to_string()));
+
+            // the mirror administrated by the controller keeps its code id
+            let contract_info = app
+                .wrap()
+                .query_wasm_contract_info(&contracts[2].contract_addr)
+                .unwrap();
+            assert_eq!(contract_info.code_id, contracts[2].contract_code_id);
+
+            // the mirror administrated by the wrapper runs the new code id
+            let contract_info = app
+                .wrap()
+                .query_wasm_contract_info(&mirrored_contract_addr)
+                .unwrap();
+            assert_eq!(contract_info.code_id, new_code_id);
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(wrapper_contract_addr, &QueryMsg::WrapData { index: 2 })
+                .unwrap();
+            assert_eq!(wrap_data.code_id, new_code_id);
+        }
+
+        #[test]
+        fn migrate_mirrors_past_the_last_page_does_nothing() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::MigrateMirrors {
+                        code_id: contracts[2].contract_code_id,
+                        msg: to_json_binary(&Empty {}).unwrap(),
+                        start_after: Some(1),
+                        limit: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(attribute(&res, "mirrored_collections"), None);
+            assert_eq!(attribute(&res, "last_index"), None);
+        }
+    }
+
+    mod register_collection {
+        use super::*;
+
+        #[test]
+        fn mirror_is_instantiated_with_the_custom_options() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            let code_id = app.store_code(base_cw721_contract_template());
+            let mirrored_contract_addr = register_collection(
+                &mut app,
+                &contracts,
+                Some(InstantiateOptions {
+                    admin: Some(USER2.to_string()),
+                    label: Some("custom mirror".to_string()),
+                    code_id: Some(code_id),
+                    msg_template: Some(
+                        r#"{"name":{name},"symbol":{symbol},"minter":{minter}}"#.to_string(),
+                    ),
+                }),
+            )
+            .unwrap();
+
+            // the mirror runs the given code id with the given admin
+            let contract_info = app
+                .wrap()
+                .query_wasm_contract_info(&mirrored_contract_addr)
+                .unwrap();
+            assert_eq!(contract_info.code_id, code_id);
+            assert_eq!(contract_info.admin, Some(USER2.to_string()));
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(wrapper_contract_addr, &QueryMsg::WrapData { index: 2 })
+                .unwrap();
+            assert_eq!(wrap_data.code_id, code_id);
+            assert_eq!(wrap_data.admin, Some(Addr::unchecked(USER2)));
+
+            // the placeholders of the template are replaced
+            let cw721_contract_info: ContractInfoResponse = app
+                .wrap()
+                .query_wasm_smart(&mirrored_contract_addr, &Cw721QueryMsg::ContractInfo {})
+                .unwrap();
+            assert_eq!(cw721_contract_info.name, "Another Collection");
+            assert_eq!(cw721_contract_info.symbol, "ANOTHER");
+            let minter: MinterResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    &mirrored_contract_addr,
+                    &Cw721BaseQueryMsg::<Empty>::Minter {},
+                )
+                .unwrap();
+            assert_eq!(minter.minter, Some(wrapper_contract_addr.clone()));
+        }
+
+        #[test]
+        fn invalid_instantiate_template_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = register_collection(
+                &mut app,
+                &contracts,
+                Some(InstantiateOptions {
+                    admin: None,
+                    label: None,
+                    code_id: None,
+                    msg_template: Some(
+                        r#"{"name":{name},"symbol":{symbol},"minter":{minter}"#.to_string(),
+                    ),
+                }),
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidInstantiateTemplate {
+                    val: format!(
+                        r#"{{"name":"Another Collection","symbol":"ANOTHER","minter":"{}""#,
+                        contracts[0].contract_addr
+                    )
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn placeholders_in_the_replaced_values_are_kept() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let code_id = app.store_code(base_cw721_contract_template());
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::RegisterCollection {
+                        original_collection: contracts[1].contract_addr.clone(),
+                        new_collection: MirroredData {
+                            collection_name: Some("Mirror of {symbol}".to_string()),
+                            collection_symbol: None,
+                            base_uri: None,
+                            token_id_strategy: None,
+                            token_uri_template: None,
+                            reveal: None,
+                            royalty_info: None,
+                        },
+                        instantiate_options: Some(InstantiateOptions {
+                            admin: None,
+                            label: None,
+                            code_id: Some(code_id),
+                            msg_template: Some(
+                                r#"{"name":{name},"symbol":{symbol},"minter":{minter}}"#
+                                    .to_string(),
+                            ),
+                        }),
+                    },
+                    &[],
+                )
+                .unwrap();
+            let mirrored_contract_addr = attribute(&res, "mirrored_collection").unwrap();
+
+            // the name is not scanned for the symbol placeholder
+            let cw721_contract_info: ContractInfoResponse = app
+                .wrap()
+                .query_wasm_smart(&mirrored_contract_addr, &Cw721QueryMsg::ContractInfo {})
+                .unwrap();
+            assert_eq!(cw721_contract_info.name, "Mirror of {symbol}");
+        }
+
+        #[test]
+        fn instantiate_template_without_minter_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let template = r#"{"name":{name},"symbol":{symbol},"minter":"cosmos1minter"}"#;
+            let res = register_collection(
+                &mut app,
+                &contracts,
+                Some(InstantiateOptions {
+                    admin: None,
+                    label: None,
+                    code_id: None,
+                    msg_template: Some(template.to_string()),
+                }),
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidInstantiateTemplate {
+                    val: template.to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod multiple_mirrors {
+        use super::*;
+
+        #[test]
+        fn token_is_wrapped_into_the_chosen_mirror() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+            let second_mirror_addr = register_mirror(
+                &mut app,
+                &contracts,
+                MirroredData {
+                    collection_name: Some("Second Mirror".to_string()),
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: None,
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+            );
+
+            // the original collection lists both mirrors
+            let mirrors: Vec<u64> = app
+                .wrap()
+                .query_wasm_smart(
+                    wrapper_contract_addr,
+                    &QueryMsg::OriginalCollectionInfo {
+                        collection_address: contracts[1].contract_addr.clone(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(mirrors, vec![1, 2]);
+
+            // wrap the token into the second mirror
+            mint_original(&mut app, &contracts, USER1, "1");
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &ExecuteMsg::Wrap {
+                        collection_address: contracts[1].contract_addr.clone(),
+                        token_ids: vec!["1".to_string()],
+                        mirror_index: Some(1),
+                        recipient: None,
+                        owner: None,
+                        then: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(
+                attribute(&res, "mirrored_collection"),
+                Some(second_mirror_addr.clone())
+            );
+            assert_eq!(
+                owner_of(&app, &second_mirror_addr, "1"),
+                Some(USER1.to_string())
+            );
+            assert_eq!(owner_of(&app, &contracts[2].contract_addr, "1"), None);
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(wrapper_contract_addr, &QueryMsg::WrapData { index: 2 })
+                .unwrap();
+            assert_eq!(wrap_data.escrowed, 1);
+
+            // unwrap it from the second mirror
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: second_mirror_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(wrapper_contract_addr, &QueryMsg::WrapData { index: 2 })
+                .unwrap();
+            assert_eq!(wrap_data.escrowed, 0);
+
+            // the released token can be wrapped into the first mirror
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn wrap_into_unknown_mirror_fails() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::MirrorNotFound { val: 1 }.to_string()
+            );
+        }
+    }
+
+    mod token_id_strategy {
+        use super::*;
+
+        // register a mirror with the strategy and wrap the original tokens into it
+        fn wrap_with_strategy(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            token_id_strategy: TokenIdStrategy,
+            token_ids: &[&str],
+        ) -> (String, AppResponse) {
+            let mirrored_contract_addr = register_mirror(
+                app,
+                contracts,
+                MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: Some(token_id_strategy),
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+            );
+            for token_id in token_ids {
+                mint_original(app, contracts, USER1, token_id);
+            }
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::Wrap {
+                        collection_address: contracts[1].contract_addr.clone(),
+                        token_ids: token_ids.iter().map(|id| id.to_string()).collect(),
+                        mirror_index: Some(1),
+                        recipient: None,
+                        owner: None,
+                        then: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            (mirrored_contract_addr, res)
+        }
+
+        fn query_token_id(app: &App, contracts: &[ContractInfo], msg: &QueryMsg) -> String {
+            app.wrap()
+                .query_wasm_smart(&contracts[0].contract_addr, msg)
+                .unwrap()
+        }
+
+        #[test]
+        fn prefixed_strategy_maps_both_ways() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let (mirrored_contract_addr, res) = wrap_with_strategy(
+                &mut app,
+                &contracts,
+                TokenIdStrategy::Prefixed {
+                    prefix: "mirror-".to_string(),
+                },
+                &["1"],
+            );
+            assert_eq!(
+                attribute(&res, "mirrored_token_ids"),
+                Some("mirror-1".to_string())
+            );
+            assert_eq!(
+                owner_of(&app, &mirrored_contract_addr, "mirror-1"),
+                Some(USER1.to_string())
+            );
+
+            let mirrored_token_id = query_token_id(
+                &app,
+                &contracts,
+                &QueryMsg::MirroredTokenId {
+                    collection_address: mirrored_contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+            );
+            assert_eq!(mirrored_token_id, "mirror-1");
+            let original_token_id = query_token_id(
+                &app,
+                &contracts,
+                &QueryMsg::OriginalTokenId {
+                    collection_address: mirrored_contract_addr.clone(),
+                    token_id: "mirror-1".to_string(),
+                },
+            );
+            assert_eq!(original_token_id, "1");
+
+            // unwrapping the mirrored token releases the original token
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: mirrored_contract_addr,
+                    token_ids: vec!["mirror-1".to_string()],
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn sequential_strategy_counts_per_mirror() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let (mirrored_contract_addr, res) = wrap_with_strategy(
+                &mut app,
+                &contracts,
+                TokenIdStrategy::Sequential,
+                &["5", "9"],
+            );
+            assert_eq!(
+                attribute(&res, "mirrored_token_ids"),
+                Some("1,2".to_string())
+            );
+            let original_token_id = query_token_id(
+                &app,
+                &contracts,
+                &QueryMsg::OriginalTokenId {
+                    collection_address: mirrored_contract_addr,
+                    token_id: "2".to_string(),
+                },
+            );
+            assert_eq!(original_token_id, "9");
+        }
+
+        #[test]
+        fn hashed_strategy_uses_the_sha256_of_the_token_id() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let (mirrored_contract_addr, _) =
+                wrap_with_strategy(&mut app, &contracts, TokenIdStrategy::Hashed, &["1"]);
+
+            // sha256("1")
+            let hashed_token_id =
+                "6b86b273ff34fce19d6b804eff5a3f5747ada4eaa22f1d49c01e52ddb7875b4b";
+            assert_eq!(
+                owner_of(&app, &mirrored_contract_addr, hashed_token_id),
+                Some(USER1.to_string())
+            );
+        }
+    }
+
+    mod token_link {
+        use super::*;
+
+        fn token_link(
+            app: &App,
+            contracts: &[ContractInfo],
+            collection: &str,
+        ) -> TokenLinkResponse {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::TokenLink {
+                        collection_address: collection.to_string(),
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn link_is_resolved_from_both_collections() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let expected = TokenLinkResponse {
+                original_collection: Addr::unchecked(&contracts[1].contract_addr),
+                original_token_id: "1".to_string(),
+                mirrored_collection: Some(Addr::unchecked(&contracts[2].contract_addr)),
+                mirrored_token_id: Some("1".to_string()),
+                wrapped: true,
+                escrow: Some(Escrow {
+                    wrap_data_index: 1,
+                    owner: Addr::unchecked(USER1),
+                    recipient: Addr::unchecked(USER1),
+                    status: EscrowStatus::Escrowed,
+                }),
+            };
+            assert_eq!(
+                token_link(&app, &contracts, &contracts[1].contract_addr),
+                expected
+            );
+            assert_eq!(
+                token_link(&app, &contracts, &contracts[2].contract_addr),
+                expected
+            );
+        }
+
+        #[test]
+        fn released_token_is_not_linked() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+            unwrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let link = token_link(&app, &contracts, &contracts[1].contract_addr);
+            assert!(!link.wrapped);
+            assert_eq!(link.mirrored_collection, None);
+            assert_eq!(link.escrow, None);
+
+            let link = token_link(&app, &contracts, &contracts[2].contract_addr);
+            assert!(!link.wrapped);
+            assert_eq!(link.original_token_id, "1");
+        }
+    }
+
+    mod reveal {
+        use super::*;
+
+        // register a mirror revealed at the given time and wrap the original tokens into it
+        fn register_unrevealed_mirror(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            reveal_at: Option<u64>,
+        ) -> String {
+            let reveal_at = reveal_at.map(|seconds| app.block_info().time.plus_seconds(seconds));
+            register_mirror(
+                app,
+                contracts,
+                MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: None,
+                    token_uri_template: None,
+                    reveal: Some(RevealConfig {
+                        placeholder_uri: "ipfs://placeholder.json".to_string(),
+                        reveal_at,
+                    }),
+                    royalty_info: None,
+                },
+            )
+        }
+
+        fn wrap_into_mirror(app: &mut App, contracts: &[ContractInfo], token_id: &str) {
+            mint_original(app, contracts, USER1, token_id);
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec![token_id.to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        fn token_uri(app: &App, collection_address: &str, token_id: &str) -> Option<String> {
+            app.wrap()
+                .query_wasm_smart::<NftInfoResponse<Option<Empty>>>(
+                    collection_address,
+                    &Cw721QueryMsg::NftInfo {
+                        token_id: token_id.to_string(),
+                    },
+                )
+                .unwrap()
+                .token_uri
+        }
+
+        #[test]
+        fn collection_is_revealed_at_reveal_time() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let mirrored_contract_addr =
+                register_unrevealed_mirror(&mut app, &contracts, Some(100));
+
+            // the token wrapped before reveal gets the placeholder uri
+            wrap_into_mirror(&mut app, &contracts, "1");
+            assert_eq!(
+                token_uri(&app, &mirrored_contract_addr, "1"),
+                Some("ipfs://placeholder.json".to_string())
+            );
+
+            // the token wrapped after reveal gets the original token uri
+            app.update_block(|block| block.time = block.time.plus_seconds(100));
+            wrap_into_mirror(&mut app, &contracts, "2");
+            assert_eq!(
+                token_uri(&app, &mirrored_contract_addr, "2"),
+                Some("ipfs://original/2".to_string())
+            );
+        }
+
+        #[test]
+        fn controller_reveals_the_collection() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let mirrored_contract_addr = register_unrevealed_mirror(&mut app, &contracts, None);
+
+            // only the controller can reveal the collection
+            let reveal_msg = ExecuteMsg::Reveal {
+                collection_address: mirrored_contract_addr.clone(),
+                token_ids: vec![],
+            };
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &reveal_msg,
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &reveal_msg,
+                &[],
+            )
+            .unwrap();
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::WrapData { index: 2 },
+                )
+                .unwrap();
+            assert!(wrap_data.revealed);
+
+            wrap_into_mirror(&mut app, &contracts, "1");
+            assert_eq!(
+                token_uri(&app, &mirrored_contract_addr, "1"),
+                Some("ipfs://original/1".to_string())
+            );
+        }
+
+        #[test]
+        fn reveal_updates_the_minted_tokens() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let mirrored_contract_addr = register_unrevealed_mirror(&mut app, &contracts, None);
+            wrap_into_mirror(&mut app, &contracts, "1");
+            wrap_into_mirror(&mut app, &contracts, "2");
+
+            // only the given tokens are updated
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Reveal {
+                    collection_address: mirrored_contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                token_uri(&app, &mirrored_contract_addr, "1"),
+                Some("ipfs://original/1".to_string())
+            );
+            assert_eq!(
+                token_uri(&app, &mirrored_contract_addr, "2"),
+                Some("ipfs://placeholder.json".to_string())
+            );
+        }
+    }
+
+    mod refresh_metadata {
+        use super::*;
+
+        fn refresh_metadata(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            collection_address: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(USER2),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RefreshMetadata {
+                    collection_address: collection_address.to_string(),
+                    token_ids: vec!["1".to_string()],
+                },
+                &[],
+            )
+        }
+
+        #[test]
+        fn refresh_is_rate_limited_per_token() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            // the mirrored tokens are refreshed at most once a minute
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::UpdateConfig {
+                    cw721_code_id: None,
+                    wrapper_as_mirror_admin: None,
+                    refresh_interval: Some(60),
+                    ics721: None,
+                    bridge_timeout: None,
+                    recovery_timelock: None,
+                },
+                &[],
+            )
+            .unwrap();
+            let mirrored_contract_addr = register_mirror(
+                &mut app,
+                &contracts,
+                MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: None,
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+            );
+            mint_original(&mut app, &contracts, USER1, "1");
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            // anyone can refresh the token once per interval
+            refresh_metadata(&mut app, &contracts, &mirrored_contract_addr).unwrap();
+            let res = refresh_metadata(&mut app, &contracts, &mirrored_contract_addr);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RefreshTooSoon {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            app.update_block(|block| block.time = block.time.plus_seconds(60));
+            refresh_metadata(&mut app, &contracts, &mirrored_contract_addr).unwrap();
+        }
+
+        #[test]
+        fn refresh_of_unregistered_collection_fails() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = refresh_metadata(&mut app, &contracts, &contracts[1].contract_addr);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::CollectionNotAllowed {}.to_string()
+            );
+        }
+    }
+
+    mod receive_nft {
+        use super::*;
+
+        #[test]
+        fn mirrored_token_sent_to_the_wrapper_is_unwrapped() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    Addr::unchecked(&contracts[2].contract_addr),
+                    &Cw721ExecuteMsg::<Extension, Empty>::SendNft {
+                        contract: contracts[0].contract_addr.clone(),
+                        token_id: "1".to_string(),
+                        msg: to_json_binary(&ReceiveMsg::Unwrap {}).unwrap(),
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(attribute(&res, "sender"), Some(USER1.to_string()));
+            assert_eq!(owner_of(&app, &contracts[2].contract_addr, "1"), None);
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn token_sent_by_unregistered_collection_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // the original collection is not a mirrored collection
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::SendNft {
+                    contract: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&ReceiveMsg::Unwrap {}).unwrap(),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::CollectionNotAllowed {}.to_string()
+            );
+        }
+
+        #[test]
+        fn cw721_base_mirror_requires_approval_to_unwrap() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            // register a plain cw721-base mirror
+            let code_id = app.store_code(base_cw721_contract_template());
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &ExecuteMsg::RegisterCollection {
+                        original_collection: contracts[1].contract_addr.clone(),
+                        new_collection: MirroredData {
+                            collection_name: None,
+                            collection_symbol: None,
+                            base_uri: None,
+                            token_id_strategy: None,
+                            token_uri_template: None,
+                            reveal: None,
+                            royalty_info: None,
+                        },
+                        instantiate_options: Some(InstantiateOptions {
+                            admin: None,
+                            label: None,
+                            code_id: Some(code_id),
+                            msg_template: Some(
+                                r#"{"name":{name},"symbol":{symbol},"minter":{minter}}"#
+                                    .to_string(),
+                            ),
+                        }),
+                    },
+                    &[],
+                )
+                .unwrap();
+            let mirrored_contract_addr = attribute(&res, "mirrored_collection").unwrap();
+            mint_original(&mut app, &contracts, USER1, "1");
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            // the wrapper cannot burn the token without approval
+            let unwrap_msg = ExecuteMsg::Unwrap {
+                collection_address: mirrored_contract_addr.clone(),
+                token_ids: vec!["1".to_string()],
+                recipient: None,
+                owner: None,
+                then: None,
+            };
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &unwrap_msg,
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::WrapperNotApproved {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            // once approved the token is unwrapped
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&mirrored_contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::Approve {
+                    spender: wrapper_contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(wrapper_contract_addr),
+                &unwrap_msg,
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+    }
+
+    mod approved_sender {
+        use super::*;
+
+        #[test]
+        fn operator_wraps_and_approved_spender_unwraps_for_the_owner() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // USER2 is an operator of USER1 in the original collection
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::ApproveAll {
+                    operator: USER2.to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+            wrap(&mut app, &contracts, USER2, "1").unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+
+            // USER2 is approved for the mirrored token
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::Approve {
+                    spender: USER2.to_string(),
+                    token_id: "1".to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+            unwrap(&mut app, &contracts, USER2, "1").unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn unapproved_sender_cannot_wrap_or_unwrap() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            let res = wrap(&mut app, &contracts, USER2, "1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+            let res = unwrap(&mut app, &contracts, USER2, "1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod recipient {
+        use super::*;
+
+        #[test]
+        fn wrap_and_unwrap_deliver_to_the_recipients() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // the mirrored token is minted to USER2
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER1),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &ExecuteMsg::Wrap {
+                        collection_address: contracts[1].contract_addr.clone(),
+                        token_ids: vec!["1".to_string()],
+                        mirror_index: None,
+                        recipient: Some(USER2.to_string()),
+                        owner: None,
+                        then: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(attribute(&res, "recipients"), Some(USER2.to_string()));
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER2.to_string())
+            );
+
+            // the escrow remembers both the owner and the recipient
+            let link: TokenLinkResponse = app
+                .wrap()
+                .query_wasm_smart(
+                    wrapper_contract_addr,
+                    &QueryMsg::TokenLink {
+                        collection_address: contracts[1].contract_addr.clone(),
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap();
+            let escrow = link.escrow.unwrap();
+            assert_eq!(escrow.owner, Addr::unchecked(USER1));
+            assert_eq!(escrow.recipient, Addr::unchecked(USER2));
+
+            // the original token is released to ADMIN
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(USER2),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &ExecuteMsg::Unwrap {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        token_ids: vec!["1".to_string()],
+                        recipient: Some(ADMIN.to_string()),
+                        owner: None,
+                        then: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+            assert_eq!(attribute(&res, "recipients"), Some(ADMIN.to_string()));
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(ADMIN.to_string())
+            );
+        }
+
+        #[test]
+        fn invalid_recipient_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: None,
+                    recipient: Some("Invalid Recipient".to_string()),
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            );
+            assert!(res.is_err());
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+    }
+
+    mod relayer {
+        use super::*;
+
+        fn set_relayer(app: &mut App, contracts: &[ContractInfo], msg: &ExecuteMsg) {
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                msg,
+                &[],
+            )
+            .unwrap();
+        }
+
+        fn relayed_wrap(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+            owner: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: None,
+                    recipient: None,
+                    owner: Some(owner.to_string()),
+                    then: None,
+                },
+                &[],
+            )
+        }
+
+        #[test]
+        fn controller_manages_the_relayers() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            // only the controller can add relayers
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::AddRelayer {
+                    relayer: USER1.to_string(),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            set_relayer(
+                &mut app,
+                &contracts,
+                &ExecuteMsg::AddRelayer {
+                    relayer: USER2.to_string(),
+                },
+            );
+            let relayers_query = QueryMsg::Relayers {
+                start_after: None,
+                limit: None,
+            };
+            let relayers: Vec<Addr> = app
+                .wrap()
+                .query_wasm_smart(&contracts[0].contract_addr, &relayers_query)
+                .unwrap();
+            assert_eq!(relayers, vec![Addr::unchecked(USER2)]);
+
+            set_relayer(
+                &mut app,
+                &contracts,
+                &ExecuteMsg::RemoveRelayer {
+                    relayer: USER2.to_string(),
+                },
+            );
+            let relayers: Vec<Addr> = app
+                .wrap()
+                .query_wasm_smart(&contracts[0].contract_addr, &relayers_query)
+                .unwrap();
+            assert!(relayers.is_empty());
+        }
+
+        #[test]
+        fn relayer_wraps_and_unwraps_for_the_owner() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            set_relayer(
+                &mut app,
+                &contracts,
+                &ExecuteMsg::AddRelayer {
+                    relayer: USER2.to_string(),
+                },
+            );
+
+            // USER1 lets the relayer move its original tokens
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::ApproveAll {
+                    operator: USER2.to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+            let res = relayed_wrap(&mut app, &contracts, USER2, USER1).unwrap();
+            assert_eq!(attribute(&res, "relayer"), Some(USER2.to_string()));
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+
+            // USER1 lets the relayer move its mirrored tokens
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::ApproveAll {
+                    operator: USER2.to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(USER2),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: None,
+                    owner: Some(USER1.to_string()),
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn only_relayers_act_for_the_given_owner() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // USER2 is not a relayer
+            let res = relayed_wrap(&mut app, &contracts, USER2, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            // the relayer cannot act for someone not owning the token
+            set_relayer(
+                &mut app,
+                &contracts,
+                &ExecuteMsg::AddRelayer {
+                    relayer: USER2.to_string(),
+                },
+            );
+            let res = relayed_wrap(&mut app, &contracts, USER2, ADMIN);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn relayer_needs_the_approval_of_the_owner() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            set_relayer(
+                &mut app,
+                &contracts,
+                &ExecuteMsg::AddRelayer {
+                    relayer: USER2.to_string(),
+                },
+            );
+
+            // USER1 only approves the wrapper
+            mint_original(&mut app, &contracts, USER1, "1");
+            let res = relayed_wrap(&mut app, &contracts, USER2, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+
+            // the relayer cannot unwrap the mirrored tokens of USER1 either
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+            let res = app.execute_contract(
+                Addr::unchecked(USER2),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: Some(USER2.to_string()),
+                    owner: Some(USER1.to_string()),
+                    then: None,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+    }
+
+    mod post_wrap_action {
+        use super::*;
+
+        fn wrap_then(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+            recipient: Option<String>,
+            then: PostAction,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    mirror_index: None,
+                    recipient,
+                    owner: None,
+                    then: Some(then),
+                },
+                &[],
+            )
+        }
+
+        #[test]
+        fn mirrored_token_is_sent_to_the_contract() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let receiver_contract_addr = instantiate_receiver(&mut app);
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            let msg = to_json_binary(&"list").unwrap();
+            wrap_then(
+                &mut app,
+                &contracts,
+                USER1,
+                None,
+                PostAction {
+                    contract: receiver_contract_addr.clone(),
+                    msg: msg.clone(),
+                },
+            )
+            .unwrap();
+
+            // the contract owns the mirrored token sent by the wrapper
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(receiver_contract_addr.clone())
+            );
+            let received: Vec<Cw721ReceiveMsg> = app
+                .wrap()
+                .query_wasm_smart(&receiver_contract_addr, &ReceiverQueryMsg::Received {})
+                .unwrap();
+            assert_eq!(
+                received,
+                vec![Cw721ReceiveMsg {
+                    sender: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&PostActionMsg {
+                        beneficiary: USER1.to_string(),
+                        msg,
+                    })
+                    .unwrap(),
+                }]
+            );
+        }
+
+        #[test]
+        fn beneficiary_is_the_owner_of_the_token_wrapped_by_an_operator() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let receiver_contract_addr = instantiate_receiver(&mut app);
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            // USER2 is an operator of USER1 in the original collection
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[1].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::ApproveAll {
+                    operator: USER2.to_string(),
+                    expires: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let msg = to_json_binary(&"list").unwrap();
+            wrap_then(
+                &mut app,
+                &contracts,
+                USER2,
+                None,
+                PostAction {
+                    contract: receiver_contract_addr.clone(),
+                    msg: msg.clone(),
+                },
+            )
+            .unwrap();
+
+            // the contract reads USER1 from the message, not the operator
+            let received: Vec<Cw721ReceiveMsg> = app
+                .wrap()
+                .query_wasm_smart(&receiver_contract_addr, &ReceiverQueryMsg::Received {})
+                .unwrap();
+            assert_eq!(received[0].sender, contracts[0].contract_addr);
+            assert_eq!(
+                from_json::<PostActionMsg>(&received[0].msg).unwrap(),
+                PostActionMsg {
+                    beneficiary: USER1.to_string(),
+                    msg,
+                }
+            );
+        }
+
+        #[test]
+        fn recipient_cannot_be_set_with_a_post_action() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let receiver_contract_addr = instantiate_receiver(&mut app);
+            mint_original(&mut app, &contracts, USER1, "1");
+
+            let res = wrap_then(
+                &mut app,
+                &contracts,
+                USER1,
+                Some(USER2.to_string()),
+                PostAction {
+                    contract: receiver_contract_addr,
+                    msg: to_json_binary(&"list").unwrap(),
+                },
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecipientWithPostAction {}.to_string()
+            );
+        }
+    }
+
+    mod post_unwrap_action {
+        use super::*;
+
+        #[test]
+        fn original_token_is_sent_to_the_contract() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let receiver_contract_addr = instantiate_receiver(&mut app);
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let msg = to_json_binary(&"stake").unwrap();
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: None,
+                    owner: None,
+                    then: Some(PostAction {
+                        contract: receiver_contract_addr.clone(),
+                        msg: msg.clone(),
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // the contract owns the original token sent by the wrapper
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(receiver_contract_addr.clone())
+            );
+            let received: Vec<Cw721ReceiveMsg> = app
+                .wrap()
+                .query_wasm_smart(&receiver_contract_addr, &ReceiverQueryMsg::Received {})
+                .unwrap();
+            assert_eq!(
+                received,
+                vec![Cw721ReceiveMsg {
+                    sender: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&PostActionMsg {
+                        beneficiary: USER1.to_string(),
+                        msg,
+                    })
+                    .unwrap(),
+                }]
+            );
+        }
+
+        #[test]
+        fn recipient_cannot_be_set_with_a_post_action() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let receiver_contract_addr = instantiate_receiver(&mut app);
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Unwrap {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    recipient: Some(USER2.to_string()),
+                    owner: None,
+                    then: Some(PostAction {
+                        contract: receiver_contract_addr,
+                        msg: to_json_binary(&"stake").unwrap(),
+                    }),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecipientWithPostAction {}.to_string()
+            );
+        }
+    }
+
+    mod unwrap_and_bridge {
+        use super::*;
+
+        // wrap the token and register the channel
+        fn wrap_with_channel(app: &mut App, contracts: &[ContractInfo]) {
+            mint_original(app, contracts, USER1, "1");
+            wrap(app, contracts, USER1, "1").unwrap();
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                    chain_id: "cosmoshub-4".to_string(),
+                    bech32_prefix: None,
+                    convert_local_receiver: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        fn unwrap_and_bridge(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+            timeout_seconds: Option<u64>,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::UnwrapAndBridge {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_ids: vec!["1".to_string()],
+                    channel_id: "channel-0".to_string(),
+                    receiver: "cosmos1receiver".to_string(),
+                    timeout_seconds,
+                },
+                &[],
+            )
+        }
+
+        fn bridged(app: &App, contracts: &[ContractInfo]) -> Vec<Cw721ReceiveMsg> {
+            app.wrap()
+                .query_wasm_smart(&contracts[3].contract_addr, &ReceiverQueryMsg::Received {})
+                .unwrap()
+        }
+
+        #[test]
+        fn original_token_is_sent_to_the_ics721_contract() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_with_channel(&mut app, &contracts);
+
+            unwrap_and_bridge(&mut app, &contracts, USER1, None).unwrap();
+
+            // the mirrored token is burned and the original token is held by the ics721 contract
+            assert_eq!(owner_of(&app, &contracts[2].contract_addr, "1"), None);
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(contracts[3].contract_addr.clone())
+            );
+            let timeout = app.block_info().time.plus_seconds(DEFAULT_BRIDGE_TIMEOUT);
+            assert_eq!(
+                bridged(&app, &contracts),
+                vec![Cw721ReceiveMsg {
+                    sender: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&IbcOutgoingMsg {
+                        receiver: "cosmos1receiver".to_string(),
+                        channel_id: "channel-0".to_string(),
+                        timeout: IbcTimeout::with_timestamp(timeout),
+                        memo: None,
+                    })
+                    .unwrap(),
+                }]
+            );
+        }
+
+        #[test]
+        fn timeout_can_be_overridden() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_with_channel(&mut app, &contracts);
+
+            unwrap_and_bridge(&mut app, &contracts, USER1, Some(60)).unwrap();
+
+            let bridge_msg: IbcOutgoingMsg = from_json(&bridged(&app, &contracts)[0].msg).unwrap();
+            assert_eq!(
+                bridge_msg.timeout,
+                IbcTimeout::with_timestamp(app.block_info().time.plus_seconds(60))
+            );
+        }
+
+        #[test]
+        fn only_the_owner_bridges_the_token() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_with_channel(&mut app, &contracts);
+
+            let res = unwrap_and_bridge(&mut app, &contracts, USER2, None);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotOwnedBySender {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert!(bridged(&app, &contracts).is_empty());
+        }
+
+        #[test]
+        fn unregistered_channel_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let res = unwrap_and_bridge(&mut app, &contracts, USER1, None);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::UnknownChannel {
+                    val: "channel-0".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+    }
+
+    mod bridge {
+        use super::*;
+
+        // wrap the token, register the channel and bridge the mirrored token
+        fn wrap_and_bridge(app: &mut App, contracts: &[ContractInfo]) -> AnyResult<AppResponse> {
+            mint_original(app, contracts, USER1, "1");
+            wrap(app, contracts, USER1, "1").unwrap();
+            set_bridge_channel(app, contracts);
+            bridge(app, contracts, "channel-0")
+        }
+
+        fn set_bridge_channel(app: &mut App, contracts: &[ContractInfo]) {
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                    chain_id: "cosmoshub-4".to_string(),
+                    bech32_prefix: None,
+                    convert_local_receiver: None,
+                },
+                &[],
+            )
+            .unwrap();
+        }
+
+        fn bridge(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            channel_id: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::SendNft {
+                    contract: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    msg: to_json_binary(&ReceiveMsg::Bridge {
+                        channel_id: channel_id.to_string(),
+                        receiver: "cosmos1receiver".to_string(),
+                        timeout_seconds: None,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+        }
+
+        // the ics721 contract calls back the wrapper
+        fn ack_callback(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+            status: Ics721Status,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Ics721AckCallback(Ics721AckCallbackMsg {
+                    status,
+                    nft_contract: contracts[2].contract_addr.clone(),
+                    original_packet: NonFungibleTokenPacketData {
+                        class_id: contracts[2].contract_addr.clone(),
+                        class_uri: None,
+                        class_data: None,
+                        token_ids: vec!["1".to_string()],
+                        token_uris: None,
+                        token_data: None,
+                        sender: contracts[0].contract_addr.clone(),
+                        receiver: "cosmos1receiver".to_string(),
+                        memo: None,
+                    },
+                    msg: to_json_binary(&1u64).unwrap(),
+                }),
+                &[],
+            )
+        }
+
+        fn bridge_state(app: &App, contracts: &[ContractInfo]) -> Option<BridgeState> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::BridgeState {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        fn tokens_in_bridge_status(
+            app: &App,
+            contracts: &[ContractInfo],
+            status: BridgeStatus,
+        ) -> Vec<String> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::TokensInBridgeStatus {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        status,
+                        start_after: None,
+                        limit: None,
+                    },
+                )
+                .unwrap()
+        }
+
+        fn bridged_out(app: &App, contracts: &[ContractInfo]) -> u64 {
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::WrapData { index: 1 },
+                )
+                .unwrap();
+            wrap_data.bridged_out
+        }
+
+        #[test]
+        fn controller_manages_the_bridge_channels() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                    chain_id: "cosmoshub-4".to_string(),
+                    bech32_prefix: None,
+                    convert_local_receiver: None,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            set_bridge_channel(&mut app, &contracts);
+            let channel_info: ChannelInfo = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::BridgeChannel {
+                        channel_id: "channel-0".to_string(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(channel_info.chain_id, "cosmoshub-4");
+        }
+
+        #[test]
+        fn receiver_must_be_an_address_of_the_counterparty_chain() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                    chain_id: "cosmoshub-4".to_string(),
+                    bech32_prefix: Some("cosmos".to_string()),
+                    convert_local_receiver: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res = bridge(&mut app, &contracts, "channel-0");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidReceiver {
+                    val: "cosmos1receiver".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn wrapped_token_is_bridged_once_acknowledged() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+
+            // the mirrored token is held by the ics721 contract until acknowledged
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(contracts[3].contract_addr.clone())
+            );
+            assert_eq!(
+                bridge_state(&app, &contracts),
+                Some(BridgeState::InFlight {
+                    channel: "channel-0".to_string(),
+                    chain: "cosmoshub-4".to_string(),
+                    owner: Addr::unchecked(USER1),
+                    window_start: None,
+                })
+            );
+            assert_eq!(
+                tokens_in_bridge_status(&app, &contracts, BridgeStatus::InFlight),
+                vec!["1".to_string()]
+            );
+            assert!(tokens_in_bridge_status(&app, &contracts, BridgeStatus::Wrapped).is_empty());
+
+            // only the ics721 contract can call back
+            let res = ack_callback(&mut app, &contracts, USER1, Ics721Status::Success);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Success,
+            )
+            .unwrap();
+            assert_eq!(
+                bridge_state(&app, &contracts),
+                Some(BridgeState::Bridged {
+                    chain: "cosmoshub-4".to_string(),
+                    channel: "channel-0".to_string(),
+                    window_start: None,
+                })
+            );
+            assert_eq!(bridged_out(&app, &contracts), 1);
+        }
+
+        #[test]
+        fn token_in_flight_is_bridged_after_its_channel_is_removed() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RemoveBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            // the counterparty chain is kept from when the token was sent
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Success,
+            )
+            .unwrap();
+            assert_eq!(
+                bridge_state(&app, &contracts),
+                Some(BridgeState::Bridged {
+                    chain: "cosmoshub-4".to_string(),
+                    channel: "channel-0".to_string(),
+                    window_start: None,
+                })
+            );
+        }
+
+        #[test]
+        fn returned_token_is_synced() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Success,
+            )
+            .unwrap();
+
+            let sync_bridge_states = |app: &mut App| {
+                app.execute_contract(
+                    Addr::unchecked(USER2),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::SyncBridgeStates {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        token_ids: vec!["1".to_string()],
+                    },
+                    &[],
+                )
+                .unwrap()
+            };
+
+            // the token is still held by the ics721 contract
+            let res = sync_bridge_states(&mut app);
+            assert_eq!(attribute(&res, "returned_token_ids"), None);
+            assert_eq!(bridged_out(&app, &contracts), 1);
+
+            // the ics721 contract releases the token sent back from the other chain
+            app.execute_contract(
+                Addr::unchecked(&ics721_contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: USER1.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            let res = sync_bridge_states(&mut app);
+            assert_eq!(attribute(&res, "returned_token_ids"), Some("1".to_string()));
+            assert_eq!(bridge_state(&app, &contracts), Some(BridgeState::Returned));
+            assert_eq!(bridged_out(&app, &contracts), 0);
+
+            // the returned token can be unwrapped
+            unwrap(&mut app, &contracts, USER1, "1").unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn tokens_in_bridge_status_are_paginated_per_collection() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            for token_id in ["1", "2", "3"] {
+                mint_original(&mut app, &contracts, USER1, token_id);
+                wrap(&mut app, &contracts, USER1, token_id).unwrap();
+            }
+
+            // the tokens of another mirror are not listed
+            let mirrored_contract_addr = register_mirror(
+                &mut app,
+                &contracts,
+                MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: None,
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+            );
+            mint_original(&mut app, &contracts, USER1, "0");
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::Wrap {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_ids: vec!["0".to_string()],
+                    mirror_index: Some(1),
+                    recipient: None,
+                    owner: None,
+                    then: None,
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(
+                owner_of(&app, &mirrored_contract_addr, "0"),
+                Some(USER1.to_string())
+            );
+
+            let page = |app: &App, start_after: Option<&str>| -> Vec<String> {
+                app.wrap()
+                    .query_wasm_smart(
+                        &contracts[0].contract_addr,
+                        &QueryMsg::TokensInBridgeStatus {
+                            collection_address: contracts[2].contract_addr.clone(),
+                            status: BridgeStatus::Wrapped,
+                            start_after: start_after.map(|token_id| token_id.to_string()),
+                            limit: Some(2),
+                        },
+                    )
+                    .unwrap()
+            };
+            assert_eq!(page(&app, None), vec!["1".to_string(), "2".to_string()]);
+            assert_eq!(page(&app, Some("2")), vec!["3".to_string()]);
+            assert!(page(&app, Some("3")).is_empty());
+        }
+
+        #[test]
+        fn failed_transfer_returns_the_token_to_its_owner() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+
+            // the ics721 contract refunds the token to the wrapper before calling back
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            app.execute_contract(
+                Addr::unchecked(&ics721_contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Failed("timeout".to_string()),
+            )
+            .unwrap();
+
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+            assert_eq!(bridge_state(&app, &contracts), Some(BridgeState::Returned));
+            assert_eq!(bridged_out(&app, &contracts), 0);
+        }
+
+        fn set_rate_limit(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetRateLimit {
+                    channel_id: "channel-0".to_string(),
+                    max_tokens: 1,
+                    window_seconds: 3600,
+                },
+                &[],
+            )
+        }
+
+        // wrap and bridge a second token through the same channel
+        fn wrap_and_bridge_second(
+            app: &mut App,
+            contracts: &[ContractInfo],
+        ) -> AnyResult<AppResponse> {
+            mint_original(app, contracts, USER1, "2");
+            wrap(app, contracts, USER1, "2").unwrap();
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::SendNft {
+                    contract: contracts[0].contract_addr.clone(),
+                    token_id: "2".to_string(),
+                    msg: to_json_binary(&ReceiveMsg::Bridge {
+                        channel_id: "channel-0".to_string(),
+                        receiver: "cosmos1receiver".to_string(),
+                        timeout_seconds: None,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+        }
+
+        fn rate_limit(app: &App, contracts: &[ContractInfo]) -> Option<RateLimit> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::RateLimit {
+                        channel_id: "channel-0".to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn controller_manages_the_rate_limits() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = set_rate_limit(&mut app, &contracts, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            set_rate_limit(&mut app, &contracts, CONTROLLER).unwrap();
+            assert_eq!(
+                rate_limit(&app, &contracts),
+                Some(RateLimit {
+                    max_tokens: 1,
+                    window_seconds: 3600,
+                    window_start: app.block_info().time,
+                    used: 0,
+                })
+            );
+
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RemoveRateLimit {
+                    channel_id: "channel-0".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(rate_limit(&app, &contracts), None);
+        }
+
+        #[test]
+        fn channel_is_limited_in_a_window() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            set_rate_limit(&mut app, &contracts, CONTROLLER).unwrap();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+
+            let res = wrap_and_bridge_second(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RateLimitExceeded {
+                    val: "channel-0".to_string()
+                }
+                .to_string()
+            );
+
+            // the usage is reset in the next window
+            app.update_block(|block| block.time = block.time.plus_seconds(3600));
+            app.execute_contract(
+                Addr::unchecked(USER1),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::SendNft {
+                    contract: contracts[0].contract_addr.clone(),
+                    token_id: "2".to_string(),
+                    msg: to_json_binary(&ReceiveMsg::Bridge {
+                        channel_id: "channel-0".to_string(),
+                        receiver: "cosmos1receiver".to_string(),
+                        timeout_seconds: None,
+                    })
+                    .unwrap(),
+                },
+                &[],
+            )
+            .unwrap();
+            assert_eq!(rate_limit(&app, &contracts).unwrap().used, 1);
+        }
+
+        #[test]
+        fn token_returned_in_a_later_window_keeps_its_usage() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            set_rate_limit(&mut app, &contracts, CONTROLLER).unwrap();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+
+            // the second token is counted in the next window
+            app.update_block(|block| block.time = block.time.plus_seconds(3600));
+            wrap_and_bridge_second(&mut app, &contracts).unwrap();
+
+            // the first token fails in the next window
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            app.execute_contract(
+                Addr::unchecked(&ics721_contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Failed("timeout".to_string()),
+            )
+            .unwrap();
+            assert_eq!(rate_limit(&app, &contracts).unwrap().used, 1);
+        }
+
+        #[test]
+        fn empty_rate_limit_window_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetRateLimit {
+                    channel_id: "channel-0".to_string(),
+                    max_tokens: 1,
+                    window_seconds: 0,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidRateLimitWindow { val: 0 }.to_string()
+            );
+        }
+
+        #[test]
+        fn returned_token_does_not_count_against_the_limit() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            set_rate_limit(&mut app, &contracts, CONTROLLER).unwrap();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            assert_eq!(rate_limit(&app, &contracts).unwrap().used, 1);
+
+            // the ics721 contract refunds the token to the wrapper before calling back
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            app.execute_contract(
+                Addr::unchecked(&ics721_contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Failed("timeout".to_string()),
+            )
+            .unwrap();
+            assert_eq!(rate_limit(&app, &contracts).unwrap().used, 0);
+
+            // the returned token can be bridged again in the same window
+            bridge(&mut app, &contracts, "channel-0").unwrap();
+        }
+
+        fn propose_recovery(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::ProposeRecovery {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                    recipient: USER2.to_string(),
+                },
+                &[],
+            )
+        }
+
+        fn execute_recovery(app: &mut App, contracts: &[ContractInfo]) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(USER2),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::ExecuteRecovery {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+        }
+
+        fn recovery(app: &App, contracts: &[ContractInfo]) -> Option<Recovery> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::Recovery {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        token_id: "1".to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn only_stranded_tokens_are_recovered() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            // the mirrored token is still on this chain
+            let res = propose_recovery(&mut app, &contracts, CONTROLLER);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotStranded {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            set_bridge_channel(&mut app, &contracts);
+            bridge(&mut app, &contracts, "channel-0").unwrap();
+            let res = propose_recovery(&mut app, &contracts, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+        }
+
+        #[test]
+        fn stranded_token_is_recovered_after_the_timelock() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &contracts[3].contract_addr.clone(),
+                Ics721Status::Success,
+            )
+            .unwrap();
+
+            propose_recovery(&mut app, &contracts, CONTROLLER).unwrap();
+            let executable_at = app
+                .block_info()
+                .time
+                .plus_seconds(DEFAULT_RECOVERY_TIMELOCK);
+            assert_eq!(
+                recovery(&app, &contracts),
+                Some(Recovery {
+                    recipient: Addr::unchecked(USER2),
+                    executable_at,
+                })
+            );
+
+            let res = execute_recovery(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecoveryTimelocked {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+
+            // anyone executes the recovery once the timelock is over
+            app.update_block(|block| block.time = executable_at);
+            execute_recovery(&mut app, &contracts).unwrap();
+
+            // the stranded mirrored token is burnt and the original token is released
+            assert_eq!(owner_of(&app, &contracts[2].contract_addr, "1"), None);
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(USER2.to_string())
+            );
+            assert_eq!(bridge_state(&app, &contracts), None);
+            assert_eq!(bridged_out(&app, &contracts), 0);
+            assert_eq!(recovery(&app, &contracts), None);
+
+            // the recovery cannot be executed twice
+            let res = execute_recovery(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecoveryNotFound {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn token_returned_during_the_timelock_is_not_recovered() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            propose_recovery(&mut app, &contracts, CONTROLLER).unwrap();
+
+            // the ics721 contract releases the token sent back from the other chain
+            app.execute_contract(
+                Addr::unchecked(&contracts[3].contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: USER1.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(DEFAULT_RECOVERY_TIMELOCK)
+            });
+            let res = execute_recovery(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::NotStranded {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn recovery_does_not_outlive_its_bridge() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            propose_recovery(&mut app, &contracts, CONTROLLER).unwrap();
+
+            // the token comes back and its owner bridges it again
+            app.execute_contract(
+                Addr::unchecked(&contracts[3].contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: USER1.to_string(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            bridge(&mut app, &contracts, "channel-0").unwrap();
+            assert_eq!(recovery(&app, &contracts), None);
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(DEFAULT_RECOVERY_TIMELOCK)
+            });
+            let res = execute_recovery(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecoveryNotFound {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(contracts[0].contract_addr.clone())
+            );
+        }
+
+        #[test]
+        fn failed_transfer_drops_the_recovery() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            propose_recovery(&mut app, &contracts, CONTROLLER).unwrap();
+
+            // the ics721 contract refunds the token to the wrapper before calling back
+            let ics721_contract_addr = contracts[3].contract_addr.clone();
+            app.execute_contract(
+                Addr::unchecked(&ics721_contract_addr),
+                Addr::unchecked(&contracts[2].contract_addr),
+                &Cw721ExecuteMsg::<Extension, Empty>::TransferNft {
+                    recipient: contracts[0].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            ack_callback(
+                &mut app,
+                &contracts,
+                &ics721_contract_addr,
+                Ics721Status::Failed("timeout".to_string()),
+            )
+            .unwrap();
+            assert_eq!(recovery(&app, &contracts), None);
+        }
+
+        #[test]
+        fn recovery_timelock_cannot_be_shortened() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::UpdateConfig {
+                    cw721_code_id: None,
+                    wrapper_as_mirror_admin: None,
+                    refresh_interval: None,
+                    ics721: None,
+                    bridge_timeout: None,
+                    recovery_timelock: Some(60),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecoveryTimelockTooShort {
+                    val: 60,
+                    min: DEFAULT_RECOVERY_TIMELOCK,
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn controller_cancels_a_recovery() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            wrap_and_bridge(&mut app, &contracts).unwrap();
+            propose_recovery(&mut app, &contracts, CONTROLLER).unwrap();
+
+            let cancel_recovery = |app: &mut App, sender: &str| {
+                app.execute_contract(
+                    Addr::unchecked(sender),
+                    Addr::unchecked(&contracts[0].contract_addr),
+                    &ExecuteMsg::CancelRecovery {
+                        collection_address: contracts[2].contract_addr.clone(),
+                        token_id: "1".to_string(),
+                    },
+                    &[],
+                )
+            };
+            let res = cancel_recovery(&mut app, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            cancel_recovery(&mut app, CONTROLLER).unwrap();
+            assert_eq!(recovery(&app, &contracts), None);
+
+            app.update_block(|block| {
+                block.time = block.time.plus_seconds(DEFAULT_RECOVERY_TIMELOCK)
+            });
+            let res = execute_recovery(&mut app, &contracts);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::RecoveryNotFound {
+                    val: "1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "1"),
+                Some(contracts[0].contract_addr.clone())
+            );
+        }
+
+        #[test]
+        fn recovery_of_an_unregistered_collection_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            // the original collection is not a mirrored collection
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::CancelRecovery {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::CollectionNotAllowed {}.to_string()
+            );
+
+            let res: StdResult<Option<Recovery>> = app.wrap().query_wasm_smart(
+                &contracts[0].contract_addr,
+                &QueryMsg::Recovery {
+                    collection_address: contracts[1].contract_addr.clone(),
+                    token_id: "1".to_string(),
+                },
+            );
+            assert!(res
+                .unwrap_err()
+                .to_string()
+                .contains(&ContractError::CollectionNotAllowed {}.to_string()));
+        }
+
+        #[test]
+        fn unregistered_channel_is_rejected() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "1");
+            wrap(&mut app, &contracts, USER1, "1").unwrap();
+
+            let res = bridge(&mut app, &contracts, "channel-1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::UnknownChannel {
+                    val: "channel-1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(bridge_state(&app, &contracts), Some(BridgeState::Wrapped));
+        }
+    }
+
+    mod provenance {
+        use super::*;
+
+        fn provenance(
+            app: &App,
+            contracts: &[ContractInfo],
+            collection_address: &str,
+        ) -> Option<Provenance> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::Provenance {
+                        collection_address: collection_address.to_string(),
+                    },
+                )
+                .unwrap()
+        }
+
+        // register a voucher collection received through channel-0 of the ics721 contract,
+        // returning the voucher and the mirrored collection
+        fn register_voucher(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            class_id: &str,
+        ) -> (String, String) {
+            let wrapper_contract_addr = &contracts[0].contract_addr;
+
+            let voucher_contract_addr = app
+                .instantiate_contract(
+                    contracts[1].contract_code_id,
+                    Addr::unchecked(ADMIN),
+                    &Cw721InstantiateMsg {
+                        name: "Voucher Collection".to_string(),
+                        symbol: "VOUCHER".to_string(),
+                        minter: ADMIN.to_string(),
+                    },
+                    &[],
+                    "test instantiate contract",
+                    None,
+                )
+                .unwrap();
+            app.execute_contract(
+                Addr::unchecked(ADMIN),
+                Addr::unchecked(&contracts[3].contract_addr),
+                &ReceiverExecuteMsg::SetClassId {
+                    contract: voucher_contract_addr.to_string(),
+                    class_id: class_id.to_string(),
+                },
+                &[],
+            )
+            .unwrap();
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(wrapper_contract_addr),
+                &ExecuteMsg::SetBridgeChannel {
+                    channel_id: "channel-0".to_string(),
+                    chain_id: "cosmoshub-4".to_string(),
+                    bech32_prefix: None,
+                    convert_local_receiver: None,
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res = app
+                .execute_contract(
+                    Addr::unchecked(CONTROLLER),
+                    Addr::unchecked(wrapper_contract_addr),
+                    &ExecuteMsg::RegisterCollection {
+                        original_collection: voucher_contract_addr.to_string(),
+                        new_collection: MirroredData {
+                            collection_name: None,
+                            collection_symbol: None,
+                            base_uri: None,
+                            token_id_strategy: None,
+                            token_uri_template: None,
+                            reveal: None,
+                            royalty_info: None,
+                        },
+                        instantiate_options: None,
+                    },
+                    &[],
+                )
+                .unwrap();
+
+            (
+                voucher_contract_addr.to_string(),
+                attribute(&res, "mirrored_collection").unwrap(),
+            )
+        }
+
+        fn original_chain(
+            app: &App,
+            contracts: &[ContractInfo],
+            mirrored_contract_addr: &str,
+        ) -> Option<String> {
+            app.wrap()
+                .query_wasm_smart::<ClassData>(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::ClassData {
+                        collection_address: mirrored_contract_addr.to_string(),
+                    },
+                )
+                .unwrap()
+                .original_chain
+        }
+
+        #[test]
+        fn voucher_collection_records_its_origin() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let (voucher_contract_addr, mirrored_contract_addr) = register_voucher(
+                &mut app,
+                &contracts,
+                "wasm.aura1ics721/channel-0/cosmos-collection",
+            );
+
+            // the original and the mirrored collection share the provenance
+            let expected = Some(Provenance {
+                class_id: "wasm.aura1ics721/channel-0/cosmos-collection".to_string(),
+                base_class_id: "cosmos-collection".to_string(),
+                channel_id: "channel-0".to_string(),
+                previous_chain: Some("cosmoshub-4".to_string()),
+                hops: 1,
+            });
+            assert_eq!(
+                provenance(&app, &contracts, &voucher_contract_addr),
+                expected
+            );
+            assert_eq!(
+                provenance(&app, &contracts, &mirrored_contract_addr),
+                expected
+            );
+
+            // the collection was created on the previous chain of a single hop
+            assert_eq!(
+                original_chain(&app, &contracts, &mirrored_contract_addr),
+                Some("cosmoshub-4".to_string())
+            );
+        }
+
+        #[test]
+        fn multi_hop_voucher_has_an_unknown_original_chain() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let (_, mirrored_contract_addr) = register_voucher(
+                &mut app,
+                &contracts,
+                "wasm.aura1ics721/channel-0/nft-transfer/channel-7/base",
+            );
+
+            let provenance = provenance(&app, &contracts, &mirrored_contract_addr).unwrap();
+            assert_eq!(provenance.previous_chain, Some("cosmoshub-4".to_string()));
+            assert_eq!(provenance.hops, 2);
+            assert_eq!(
+                original_chain(&app, &contracts, &mirrored_contract_addr),
+                None
+            );
+        }
+
+        #[test]
+        fn native_collection_has_no_provenance() {
+            // get integration test app and contracts
+            let (app, contracts) = instantiate_contracts();
+
+            assert_eq!(
+                provenance(&app, &contracts, &contracts[1].contract_addr),
+                None
+            );
+            assert_eq!(
+                provenance(&app, &contracts, &contracts[2].contract_addr),
+                None
+            );
+        }
+    }
+
+    mod class_data {
+        use super::*;
+
+        fn mirrored_data(royalty_percentage: u64) -> MirroredData {
+            MirroredData {
+                collection_name: Some("Royal Collection".to_string()),
+                collection_symbol: Some("ROYAL".to_string()),
+                base_uri: Some("ipfs://royal/".to_string()),
+                token_id_strategy: None,
+                token_uri_template: None,
+                reveal: None,
+                royalty_info: Some(RoyaltyInfo {
+                    payment_address: USER2.to_string(),
+                    royalty_percentage,
+                }),
+            }
+        }
+
+        #[test]
+        fn class_data_carries_the_mirrored_data() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let mirrored_contract_addr = register_mirror(&mut app, &contracts, mirrored_data(5));
+
+            let expected = ClassData {
+                name: "Royal Collection".to_string(),
+                symbol: "ROYAL".to_string(),
+                original_collection: contracts[1].contract_addr.clone(),
+                original_chain: Some(app.block_info().chain_id),
+                base_uri: Some("ipfs://royal/".to_string()),
+                royalty_info: Some(RoyaltyInfo {
+                    payment_address: USER2.to_string(),
+                    royalty_percentage: 5,
+                }),
+                wrapper: contracts[0].contract_addr.clone(),
+            };
+
+            // the wrapper and the mirrored collection answer the same class data
+            let class_data: ClassData = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::ClassData {
+                        collection_address: mirrored_contract_addr.clone(),
+                    },
+                )
+                .unwrap();
+            assert_eq!(class_data, expected);
+
+            let class_data: ClassData = app
+                .wrap()
+                .query_wasm_smart(
+                    &mirrored_contract_addr,
+                    &Cw721BaseQueryMsg::Extension {
+                        msg: mirrored_cw721::msg::QueryExt::ClassData {},
+                    },
+                )
+                .unwrap();
+            assert_eq!(class_data, expected);
+        }
+
+        #[test]
+        fn royalty_cannot_exceed_the_sale_price() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RegisterCollection {
+                    original_collection: contracts[1].contract_addr.clone(),
+                    new_collection: mirrored_data(101),
+                    instantiate_options: None,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidRoyaltyPercentage { val: 101 }.to_string()
+            );
+        }
+    }
+
+    mod ics721_policy {
+        use super::*;
+
+        fn set_ics721_policy(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetIcs721Policy {
+                    policy: Some(Ics721Policy {
+                        max_token_id_length: 8,
+                        token_id_extra_chars: "-_".to_string(),
+                        max_name_length: 16,
+                        max_symbol_length: 6,
+                    }),
+                },
+                &[],
+            )
+        }
+
+        #[test]
+        fn only_controller_sets_the_policy() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            let res = set_ics721_policy(&mut app, &contracts, USER1);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            let res = set_ics721_policy(&mut app, &contracts, CONTROLLER).unwrap();
+            assert_eq!(attribute(&res, "enabled"), Some("true".to_string()));
+        }
+
+        #[test]
+        fn incompatible_token_is_not_wrapped() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            mint_original(&mut app, &contracts, USER1, "token/1");
+            mint_original(&mut app, &contracts, USER1, "token-1");
+
+            set_ics721_policy(&mut app, &contracts, CONTROLLER).unwrap();
+
+            let res = wrap(&mut app, &contracts, USER1, "token/1");
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidTokenIdCharacters {
+                    val: "token/1".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                owner_of(&app, &contracts[1].contract_addr, "token/1"),
+                Some(USER1.to_string())
+            );
+
+            wrap(&mut app, &contracts, USER1, "token-1").unwrap();
+            assert_eq!(
+                owner_of(&app, &contracts[2].contract_addr, "token-1"),
+                Some(USER1.to_string())
+            );
+        }
+
+        #[test]
+        fn incompatible_collection_is_not_registered() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            set_ics721_policy(&mut app, &contracts, CONTROLLER).unwrap();
+
+            let res = app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::RegisterCollection {
+                    original_collection: contracts[1].contract_addr.clone(),
+                    new_collection: MirroredData {
+                        collection_name: Some("Mirrored".to_string()),
+                        collection_symbol: Some("MIR-1".to_string()),
+                        base_uri: None,
+                        token_id_strategy: None,
+                        token_uri_template: None,
+                        reveal: None,
+                        royalty_info: None,
+                    },
+                    instantiate_options: None,
+                },
+                &[],
+            );
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidCollectionSymbol {
+                    val: "MIR-1".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        fn check_token_ids(
+            app: &App,
+            contracts: &[ContractInfo],
+            mirror_index: Option<u32>,
+        ) -> Vec<TokenIdCheckResponse> {
+            app.wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::CheckTokenIds {
+                        collection_address: contracts[1].contract_addr.clone(),
+                        mirror_index,
+                        token_ids: vec!["token/1".to_string(), "token-1".to_string()],
+                    },
+                )
+                .unwrap()
+        }
+
+        #[test]
+        fn token_ids_are_checked() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            // all token ids are compatible without a policy
+            let res = check_token_ids(&app, &contracts, None);
+            assert!(res.iter().all(|check| check.error.is_none()));
+
+            set_ics721_policy(&mut app, &contracts, CONTROLLER).unwrap();
+            let res = check_token_ids(&app, &contracts, None);
+            assert_eq!(
+                res,
+                vec![
+                    TokenIdCheckResponse {
+                        token_id: "token/1".to_string(),
+                        mirrored_token_id: "token/1".to_string(),
+                        error: Some(
+                            ContractError::InvalidTokenIdCharacters {
+                                val: "token/1".to_string()
+                            }
+                            .to_string()
+                        ),
+                    },
+                    TokenIdCheckResponse {
+                        token_id: "token-1".to_string(),
+                        mirrored_token_id: "token-1".to_string(),
+                        error: None,
+                    },
+                ]
+            );
+        }
+
+        #[test]
+        fn derived_token_ids_are_checked() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+
+            // the mirrored token ids are sequential in the second mirror
+            register_mirror(
+                &mut app,
+                &contracts,
+                MirroredData {
+                    collection_name: None,
+                    collection_symbol: None,
+                    base_uri: None,
+                    token_id_strategy: Some(TokenIdStrategy::Sequential),
+                    token_uri_template: None,
+                    reveal: None,
+                    royalty_info: None,
+                },
+            );
+            set_ics721_policy(&mut app, &contracts, CONTROLLER).unwrap();
+            assert_eq!(
+                check_token_ids(&app, &contracts, Some(1)),
+                vec![
+                    TokenIdCheckResponse {
+                        token_id: "token/1".to_string(),
+                        mirrored_token_id: "1".to_string(),
+                        error: None,
+                    },
+                    TokenIdCheckResponse {
+                        token_id: "token-1".to_string(),
+                        mirrored_token_id: "2".to_string(),
+                        error: None,
+                    },
+                ]
+            );
+        }
+    }
+
+    mod update_collection_info {
+        use super::*;
+
+        fn update_collection_info(
+            app: &mut App,
+            contracts: &[ContractInfo],
+            sender: &str,
+            symbol: Option<&str>,
+        ) -> AnyResult<AppResponse> {
+            app.execute_contract(
+                Addr::unchecked(sender),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::UpdateCollectionInfo {
+                    collection_address: contracts[2].contract_addr.clone(),
+                    name: Some("Renamed".to_string()),
+                    symbol: symbol.map(|symbol| symbol.to_string()),
+                },
+                &[],
+            )
+        }
+
+        #[test]
+        fn controller_updates_the_mirrored_collection() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            let symbol = app
+                .wrap()
+                .query_wasm_smart::<ContractInfoResponse>(
+                    &contracts[2].contract_addr,
+                    &Cw721QueryMsg::ContractInfo {},
+                )
+                .unwrap()
+                .symbol;
+
+            let res = update_collection_info(&mut app, &contracts, USER1, None);
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::Unauthorized {}.to_string()
+            );
+
+            // the symbol is kept when not given
+            update_collection_info(&mut app, &contracts, CONTROLLER, None).unwrap();
+            let contract_info: ContractInfoResponse = app
+                .wrap()
+                .query_wasm_smart(&contracts[2].contract_addr, &Cw721QueryMsg::ContractInfo {})
+                .unwrap();
+            assert_eq!(contract_info.name, "Renamed");
+            assert_eq!(contract_info.symbol, symbol);
+
+            let wrap_data: WrapData = app
+                .wrap()
+                .query_wasm_smart(
+                    &contracts[0].contract_addr,
+                    &QueryMsg::WrapData { index: 1 },
+                )
+                .unwrap();
+            assert_eq!(
+                wrap_data.mirrored_data.collection_name,
+                Some("Renamed".to_string())
+            );
+        }
+
+        #[test]
+        fn updated_collection_must_follow_the_ics721_policy() {
+            // get integration test app and contracts
+            let (mut app, contracts) = instantiate_contracts();
+            app.execute_contract(
+                Addr::unchecked(CONTROLLER),
+                Addr::unchecked(&contracts[0].contract_addr),
+                &ExecuteMsg::SetIcs721Policy {
+                    policy: Some(Ics721Policy {
+                        max_token_id_length: 8,
+                        token_id_extra_chars: "-_".to_string(),
+                        max_name_length: 16,
+                        max_symbol_length: 6,
+                    }),
+                },
+                &[],
+            )
+            .unwrap();
+
+            let res = update_collection_info(&mut app, &contracts, CONTROLLER, Some("MIR-1"));
+            assert_eq!(
+                res.unwrap_err().root_cause().to_string(),
+                ContractError::InvalidCollectionSymbol {
+                    val: "MIR-1".to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+}
@@ -1,3 +1,4 @@
 #[cfg(test)]
 mod env_setup;
 mod integration_test;
+mod unit_test;
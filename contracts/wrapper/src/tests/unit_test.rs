@@ -0,0 +1,553 @@
+#![cfg(test)]
+mod tests {
+    use crate::contract::{
+        migrate, DEFAULT_BRIDGE_TIMEOUT, DEFAULT_RECOVERY_TIMELOCK, DEFAULT_REFRESH_INTERVAL,
+    };
+    use crate::error::ContractError;
+    use crate::ics721::{
+        parse_class_trace, validate_collection_info, validate_receiver, validate_token_id,
+    };
+    use crate::msg::MigrateMsg;
+    use crate::state::{
+        ChannelInfo, Ics721Policy, MirroredData, RevealConfig, TokenIdStrategy, CONFIG,
+        ORIGINAL_COLLECTIONS, WRAP_DATA,
+    };
+    use crate::token_uri::{is_revealed, mirrored_token_uri, render_token_uri_template};
+    use bech32::{ToBase32, Variant};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Addr, Storage, Timestamp};
+    use cw2::{get_contract_version, set_contract_version};
+    use cw_storage_plus::{Item, Map};
+
+    const CONTRACT_NAME: &str = "crates.io:wrapper-seekhype";
+    const CONTROLLER: &str = "aura10000000000000000000000000000controller";
+    const ORIGINAL_COLLECTION: &str = "aura1000000000000000000000000000000original";
+    const MIRRORED_COLLECTION: &str = "aura1000000000000000000000000000000mirrored";
+
+    mod migration {
+        use super::*;
+
+        // the mirrored data of v0.1.x
+        #[cw_serde]
+        struct MirroredDataV0_1 {
+            collection_name: Option<String>,
+            collection_symbol: Option<String>,
+            base_uri: Option<String>,
+        }
+
+        // the wrap data of v0.1.x
+        #[cw_serde]
+        struct WrapDataV0_1 {
+            original_collection: Addr,
+            mirrored_collection: Addr,
+            mirrored_data: MirroredDataV0_1,
+            active: bool,
+        }
+
+        const LEGACY_CONTROLLER: Item<Addr> = Item::new("controller");
+        const LEGACY_CW721_CODE_ID: Item<u64> = Item::new("cw721-code-id");
+        const LEGACY_TOTAL_WRAPPED: Item<u64> = Item::new("total-wrapped");
+        const LEGACY_WRAP_DATA: Map<u64, WrapDataV0_1> = Map::new("wrap-data");
+        const LEGACY_ORIGINAL_COLLECTIONS: Map<Addr, u64> = Map::new("original-collections");
+
+        // the storage of a v0.1 wrapper with one registered collection
+        fn save_v0_1_storage(storage: &mut dyn Storage) {
+            set_contract_version(storage, CONTRACT_NAME, "0.1.0").unwrap();
+            LEGACY_CONTROLLER
+                .save(storage, &Addr::unchecked(CONTROLLER))
+                .unwrap();
+            LEGACY_CW721_CODE_ID.save(storage, &7).unwrap();
+            LEGACY_TOTAL_WRAPPED.save(storage, &1).unwrap();
+            LEGACY_WRAP_DATA
+                .save(
+                    storage,
+                    1,
+                    &WrapDataV0_1 {
+                        original_collection: Addr::unchecked(ORIGINAL_COLLECTION),
+                        mirrored_collection: Addr::unchecked(MIRRORED_COLLECTION),
+                        mirrored_data: MirroredDataV0_1 {
+                            collection_name: Some("Mirrored".to_string()),
+                            collection_symbol: None,
+                            base_uri: Some("ipfs://mirrored/".to_string()),
+                        },
+                        active: true,
+                    },
+                )
+                .unwrap();
+            LEGACY_ORIGINAL_COLLECTIONS
+                .save(storage, Addr::unchecked(ORIGINAL_COLLECTION), &1)
+                .unwrap();
+        }
+
+        #[test]
+        fn migrate_v0_1_folds_legacy_items_into_config() {
+            let mut deps = mock_dependencies();
+            save_v0_1_storage(deps.as_mut().storage);
+
+            migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+            // the legacy items are folded into the config
+            let config = CONFIG.load(deps.as_ref().storage).unwrap();
+            assert_eq!(config.controller, Addr::unchecked(CONTROLLER));
+            assert_eq!(config.cw721_code_id, 7);
+            assert_eq!(config.total_wrapped, 1);
+            assert!(!config.paused);
+            assert!(!config.wrapper_as_mirror_admin);
+            assert_eq!(config.refresh_interval, DEFAULT_REFRESH_INTERVAL);
+            assert_eq!(config.ics721, None);
+            assert_eq!(config.bridge_timeout, DEFAULT_BRIDGE_TIMEOUT);
+            assert_eq!(config.recovery_timelock, DEFAULT_RECOVERY_TIMELOCK);
+            assert_eq!(config.ics721_policy, None);
+            assert!(!LEGACY_CONTROLLER.exists(deps.as_ref().storage));
+            assert!(!LEGACY_CW721_CODE_ID.exists(deps.as_ref().storage));
+            assert!(!LEGACY_TOTAL_WRAPPED.exists(deps.as_ref().storage));
+
+            // the contract version is updated
+            let version = get_contract_version(deps.as_ref().storage).unwrap();
+            assert_eq!(version.contract, CONTRACT_NAME);
+            assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+        }
+
+        #[test]
+        fn migrate_v0_1_backfills_wrap_data() {
+            let mut deps = mock_dependencies();
+            save_v0_1_storage(deps.as_mut().storage);
+
+            migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+            // the mirrored collections run the code id of the config with the controller as admin
+            let wrap_data = WRAP_DATA.load(deps.as_ref().storage, 1).unwrap();
+            assert_eq!(
+                wrap_data.original_collection,
+                Addr::unchecked(ORIGINAL_COLLECTION)
+            );
+            assert_eq!(
+                wrap_data.mirrored_collection,
+                Addr::unchecked(MIRRORED_COLLECTION)
+            );
+            assert_eq!(
+                wrap_data.mirrored_data.base_uri,
+                Some("ipfs://mirrored/".to_string())
+            );
+            assert!(wrap_data.active);
+            assert_eq!(wrap_data.code_id, 7);
+            assert_eq!(wrap_data.admin, Some(Addr::unchecked(CONTROLLER)));
+            assert_eq!(wrap_data.escrowed, 0);
+
+            // the tokens of v0.1 keep the original token id
+            assert_eq!(
+                wrap_data.mirrored_data.token_id_strategy,
+                Some(TokenIdStrategy::Identity)
+            );
+            assert_eq!(wrap_data.token_id_sequence, 0);
+            assert_eq!(wrap_data.mirrored_data.token_uri_template, None);
+            assert_eq!(wrap_data.mirrored_data.reveal, None);
+            assert_eq!(wrap_data.mirrored_data.royalty_info, None);
+            assert!(!wrap_data.revealed);
+            assert_eq!(wrap_data.provenance, None);
+
+            // the original collection keeps its only mirror
+            let mirrors = ORIGINAL_COLLECTIONS
+                .load(deps.as_ref().storage, Addr::unchecked(ORIGINAL_COLLECTION))
+                .unwrap();
+            assert_eq!(mirrors, vec![1]);
+        }
+
+        #[test]
+        fn migrate_rejects_other_contracts() {
+            let mut deps = mock_dependencies();
+            set_contract_version(deps.as_mut().storage, "crates.io:cw721-base", "0.1.0").unwrap();
+
+            let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                ContractError::CannotMigrateContract {
+                    previous_contract: "crates.io:cw721-base".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn migrate_rejects_downgrades() {
+            let mut deps = mock_dependencies();
+            set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+
+            let err = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                ContractError::CannotMigrateVersion {
+                    previous_version: "99.0.0".to_string(),
+                    new_version: env!("CARGO_PKG_VERSION").to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+
+    mod token_uri {
+        use super::*;
+
+        fn mirrored_data(base_uri: Option<&str>, token_uri_template: Option<&str>) -> MirroredData {
+            MirroredData {
+                collection_name: None,
+                collection_symbol: None,
+                base_uri: base_uri.map(|base_uri| base_uri.to_string()),
+                token_id_strategy: None,
+                token_uri_template: token_uri_template.map(|template| template.to_string()),
+                reveal: None,
+                royalty_info: None,
+            }
+        }
+
+        #[test]
+        fn template_placeholders_are_rendered() {
+            let token_uri = render_token_uri_template(
+                "ipfs://cid/{token_id}/{original_token_id}?from={original_uri}",
+                "m-7",
+                "7",
+                Some("ipfs://original/7"),
+            )
+            .unwrap();
+            assert_eq!(token_uri, "ipfs://cid/m-7/7?from=ipfs://original/7");
+
+            // the missing original uri is rendered empty
+            let token_uri =
+                render_token_uri_template("{original_uri}#{token_id}", "7", "7", None).unwrap();
+            assert_eq!(token_uri, "#7");
+        }
+
+        #[test]
+        fn token_id_is_padded_and_hashed() {
+            let token_uri =
+                render_token_uri_template("ipfs://cid/{token_id:05}.json", "42", "42", None)
+                    .unwrap();
+            assert_eq!(token_uri, "ipfs://cid/00042.json");
+
+            // the width is a minimum, longer token ids are kept
+            let token_uri =
+                render_token_uri_template("{token_id:02}", "12345", "12345", None).unwrap();
+            assert_eq!(token_uri, "12345");
+
+            // sha256("1")
+            let token_uri = render_token_uri_template("{token_id_hash}", "1", "1", None).unwrap();
+            assert_eq!(
+                token_uri,
+                "6b86b273ff34fce19d6b804eff5a3f5747ada4eaa22f1d49c01e52ddb7875b4b"
+            );
+        }
+
+        #[test]
+        fn invalid_templates_are_rejected() {
+            for template in [
+                "ipfs://{token_id",
+                "{unknown}",
+                "{token_id:0x}",
+                "{token_id:5}",
+                // the padding width is capped
+                "{token_id:065}",
+                "{token_id:018446744073709551615}",
+            ] {
+                let err = render_token_uri_template(template, "1", "1", None).unwrap_err();
+                assert_eq!(
+                    err.to_string(),
+                    ContractError::InvalidTokenUriTemplate {
+                        val: template.to_string()
+                    }
+                    .to_string()
+                );
+            }
+        }
+
+        #[test]
+        fn mirrored_token_uri_prefers_template_then_base_uri() {
+            let original_uri = Some("ipfs://original/1".to_string());
+
+            // the template wins over the base uri
+            let token_uri = mirrored_token_uri(
+                &mirrored_data(Some("ipfs://base/"), Some("ipfs://template/{token_id}")),
+                true,
+                "1",
+                "1",
+                original_uri.clone(),
+            )
+            .unwrap();
+            assert_eq!(token_uri, Some("ipfs://template/1".to_string()));
+
+            // the base uri is suffixed with the mirrored token id
+            let token_uri = mirrored_token_uri(
+                &mirrored_data(Some("ipfs://base/"), None),
+                true,
+                "m-1",
+                "1",
+                original_uri.clone(),
+            )
+            .unwrap();
+            assert_eq!(token_uri, Some("ipfs://base/m-1.json".to_string()));
+
+            // otherwise the original token uri is kept
+            let token_uri = mirrored_token_uri(
+                &mirrored_data(None, None),
+                true,
+                "1",
+                "1",
+                original_uri.clone(),
+            )
+            .unwrap();
+            assert_eq!(token_uri, original_uri);
+        }
+    }
+
+    mod reveal {
+        use super::*;
+
+        fn mirrored_data(reveal_at: Option<Timestamp>) -> MirroredData {
+            MirroredData {
+                collection_name: None,
+                collection_symbol: None,
+                base_uri: Some("ipfs://base/".to_string()),
+                token_id_strategy: None,
+                token_uri_template: None,
+                reveal: Some(RevealConfig {
+                    placeholder_uri: "ipfs://placeholder.json".to_string(),
+                    reveal_at,
+                }),
+                royalty_info: None,
+            }
+        }
+
+        #[test]
+        fn collection_without_reveal_is_always_revealed() {
+            let mirrored_data = MirroredData {
+                reveal: None,
+                ..mirrored_data(None)
+            };
+            assert!(is_revealed(
+                &mirrored_data,
+                false,
+                Timestamp::from_seconds(0)
+            ));
+        }
+
+        #[test]
+        fn collection_is_revealed_manually_or_at_reveal_time() {
+            let now = Timestamp::from_seconds(1_000);
+
+            // without reveal time only the controller reveals the collection
+            assert!(!is_revealed(&mirrored_data(None), false, now));
+            assert!(is_revealed(&mirrored_data(None), true, now));
+
+            // the reveal time is inclusive
+            let mirrored_data = mirrored_data(Some(now));
+            assert!(!is_revealed(&mirrored_data, false, now.minus_seconds(1)));
+            assert!(is_revealed(&mirrored_data, false, now));
+        }
+
+        #[test]
+        fn placeholder_uri_is_used_before_reveal() {
+            let original_uri = Some("ipfs://original/1".to_string());
+
+            let token_uri =
+                mirrored_token_uri(&mirrored_data(None), false, "1", "1", original_uri.clone())
+                    .unwrap();
+            assert_eq!(token_uri, Some("ipfs://placeholder.json".to_string()));
+
+            let token_uri =
+                mirrored_token_uri(&mirrored_data(None), true, "1", "1", original_uri).unwrap();
+            assert_eq!(token_uri, Some("ipfs://base/1.json".to_string()));
+        }
+    }
+
+    mod ics721 {
+        use super::*;
+
+        #[test]
+        fn single_hop_trace_is_parsed() {
+            assert_eq!(
+                parse_class_trace("wasm.aura1ics721/channel-0/cosmos-collection"),
+                Some(("channel-0".to_string(), "cosmos-collection".to_string(), 1))
+            );
+
+            // the base class id can contain slashes
+            assert_eq!(
+                parse_class_trace("nft-transfer/channel-3/ipfs://collection"),
+                Some(("channel-3".to_string(), "ipfs://collection".to_string(), 1))
+            );
+        }
+
+        #[test]
+        fn multi_hop_trace_keeps_the_local_channel() {
+            assert_eq!(
+                parse_class_trace(
+                    "wasm.aura1ics721/channel-0/nft-transfer/channel-7/wasm.juno1ics721/channel-2/base"
+                ),
+                Some(("channel-0".to_string(), "base".to_string(), 3))
+            );
+        }
+
+        #[test]
+        fn class_id_without_trace_is_ignored() {
+            assert_eq!(parse_class_trace("aura1collection"), None);
+            assert_eq!(parse_class_trace("wasm.aura1ics721/channel-0"), None);
+            assert_eq!(parse_class_trace("port/connection-0/base"), None);
+        }
+
+        fn policy() -> Ics721Policy {
+            Ics721Policy {
+                max_token_id_length: 8,
+                token_id_extra_chars: "-_".to_string(),
+                max_name_length: 16,
+                max_symbol_length: 6,
+            }
+        }
+
+        #[test]
+        fn token_id_is_validated() {
+            assert!(validate_token_id(&policy(), "token-1_a").is_err());
+            assert!(validate_token_id(&policy(), "token-1").is_ok());
+            assert!(validate_token_id(&policy(), "12345678").is_ok());
+
+            assert_eq!(
+                validate_token_id(&policy(), "").unwrap_err().to_string(),
+                ContractError::EmptyTokenId {}.to_string()
+            );
+            assert_eq!(
+                validate_token_id(&policy(), "123456789")
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::TokenIdTooLong {
+                    val: "123456789".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                validate_token_id(&policy(), "token/1")
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::InvalidTokenIdCharacters {
+                    val: "token/1".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn collection_info_is_validated() {
+            assert!(validate_collection_info(&policy(), "Mirrored Collection", "MIR").is_err());
+            assert!(validate_collection_info(&policy(), "Mirrored", "MIR").is_ok());
+
+            assert_eq!(
+                validate_collection_info(&policy(), "", "MIR")
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::InvalidCollectionName {
+                    val: "".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                validate_collection_info(&policy(), "Mirrored", "MIRRORED")
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::InvalidCollectionSymbol {
+                    val: "MIRRORED".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                validate_collection_info(&policy(), "Mirrored", "MIR-1")
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::InvalidCollectionSymbol {
+                    val: "MIR-1".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        // the address of the same account on the chain of the prefix
+        fn address(prefix: &str) -> String {
+            bech32::encode(prefix, [7u8; 20].to_base32(), Variant::Bech32).unwrap()
+        }
+
+        fn channel(bech32_prefix: Option<&str>, convert_local_receiver: bool) -> ChannelInfo {
+            ChannelInfo {
+                chain_id: "cosmoshub-4".to_string(),
+                bech32_prefix: bech32_prefix.map(|prefix| prefix.to_string()),
+                convert_local_receiver,
+            }
+        }
+
+        #[test]
+        fn receiver_is_not_checked_without_prefix() {
+            let wrapper = Addr::unchecked(address("aura"));
+
+            assert_eq!(
+                validate_receiver(&channel(None, true), "receiver", &wrapper).unwrap(),
+                "receiver"
+            );
+        }
+
+        #[test]
+        fn receiver_must_have_the_counterparty_prefix() {
+            let wrapper = Addr::unchecked(address("aura"));
+
+            assert_eq!(
+                validate_receiver(
+                    &channel(Some("cosmos"), false),
+                    &address("cosmos"),
+                    &wrapper
+                )
+                .unwrap(),
+                address("cosmos")
+            );
+            assert_eq!(
+                validate_receiver(&channel(Some("cosmos"), false), &address("osmo"), &wrapper)
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::ReceiverPrefixMismatch {
+                    val: address("osmo"),
+                    expected: "cosmos".to_string()
+                }
+                .to_string()
+            );
+            assert_eq!(
+                validate_receiver(&channel(Some("cosmos"), false), "cosmos1receiver", &wrapper)
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::InvalidReceiver {
+                    val: "cosmos1receiver".to_string()
+                }
+                .to_string()
+            );
+        }
+
+        #[test]
+        fn local_receiver_is_converted_if_allowed() {
+            let wrapper = Addr::unchecked(address("aura"));
+
+            assert_eq!(
+                validate_receiver(&channel(Some("cosmos"), true), &address("aura"), &wrapper)
+                    .unwrap(),
+                address("cosmos")
+            );
+
+            // only the local prefix is converted
+            assert!(
+                validate_receiver(&channel(Some("cosmos"), true), &address("osmo"), &wrapper)
+                    .is_err()
+            );
+            assert_eq!(
+                validate_receiver(&channel(Some("cosmos"), false), &address("aura"), &wrapper)
+                    .unwrap_err()
+                    .to_string(),
+                ContractError::ReceiverPrefixMismatch {
+                    val: address("aura"),
+                    expected: "cosmos".to_string()
+                }
+                .to_string()
+            );
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use cosmwasm_std::Timestamp;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+use crate::state::MirroredData;
+
+// the maximum width a mirrored token id can be padded to in a token uri template
+const MAX_TOKEN_ID_WIDTH: usize = 64;
+
+/// whether the mirrored collection is revealed at the given time
+pub fn is_revealed(mirrored_data: &MirroredData, revealed: bool, now: Timestamp) -> bool {
+    match &mirrored_data.reveal {
+        Some(reveal) => revealed || matches!(reveal.reveal_at, Some(reveal_at) if reveal_at <= now),
+        None => true,
+    }
+}
+
+/// the token uri the contract mints for a mirrored token,
+/// the placeholder uri is used before reveal, then the template of mirrored data,
+/// then the base uri, then the original token uri
+pub fn mirrored_token_uri(
+    mirrored_data: &MirroredData,
+    revealed: bool,
+    mirrored_token_id: &str,
+    original_token_id: &str,
+    original_uri: Option<String>,
+) -> Result<Option<String>, ContractError> {
+    if let (false, Some(reveal)) = (revealed, &mirrored_data.reveal) {
+        return Ok(Some(reveal.placeholder_uri.clone()));
+    }
+
+    if let Some(template) = &mirrored_data.token_uri_template {
+        return Ok(Some(render_token_uri_template(
+            template,
+            mirrored_token_id,
+            original_token_id,
+            original_uri.as_deref(),
+        )?));
+    }
+
+    if let Some(base_uri) = &mirrored_data.base_uri {
+        return Ok(Some(format!("{}{}.json", base_uri, mirrored_token_id)));
+    }
+
+    Ok(original_uri)
+}
+
+/// render a token uri template, the supported placeholders are
+/// - `{token_id}`: the mirrored token id
+/// - `{token_id:0N}`: the mirrored token id padded with zeros to N characters, N up to 64
+/// - `{token_id_hash}`: the hex encoded sha256 hash of the mirrored token id
+/// - `{original_token_id}`: the original token id
+/// - `{original_uri}`: the token uri of the original token, empty if not set
+pub fn render_token_uri_template(
+    template: &str,
+    mirrored_token_id: &str,
+    original_token_id: &str,
+    original_uri: Option<&str>,
+) -> Result<String, ContractError> {
+    let mut token_uri = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        token_uri.push_str(&rest[..start]);
+        let end = rest[start..]
+            .find('}')
+            .map(|end| start + end)
+            .ok_or_else(|| ContractError::InvalidTokenUriTemplate {
+                val: template.to_string(),
+            })?;
+
+        let placeholder = &rest[start + 1..end];
+        match placeholder {
+            "token_id" => token_uri.push_str(mirrored_token_id),
+            "token_id_hash" => token_uri.push_str(&sha256_hex(mirrored_token_id)),
+            "original_token_id" => token_uri.push_str(original_token_id),
+            "original_uri" => token_uri.push_str(original_uri.unwrap_or_default()),
+            _ => {
+                let width = placeholder
+                    .strip_prefix("token_id:0")
+                    .and_then(|width| width.parse::<usize>().ok())
+                    .filter(|width| *width <= MAX_TOKEN_ID_WIDTH)
+                    .ok_or_else(|| ContractError::InvalidTokenUriTemplate {
+                        val: template.to_string(),
+                    })?;
+                token_uri.push_str(&format!("{:0>width$}", mirrored_token_id, width = width));
+            }
+        }
+
+        rest = &rest[end + 1..];
+    }
+    token_uri.push_str(rest);
+
+    Ok(token_uri)
+}
+
+/// check that a token uri template only contains the supported placeholders
+pub fn validate_token_uri_template(template: &str) -> Result<(), ContractError> {
+    render_token_uri_template(template, "", "", None).map(|_| ())
+}
+
+/// the hex encoded sha256 hash of a value
+pub fn sha256_hex(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}